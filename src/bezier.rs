@@ -0,0 +1,83 @@
+use glam::Vec2;
+
+/// how many pieces a curve is cut into to look for where a ray crosses it or which point is closest
+const SEARCH_STEPS: usize = 32;
+
+/// a cubic Bézier curve from the first to the last point, pulled towards the two in the middle.
+/// used for curved mirrors and medium sides
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bezier(pub [Vec2; 4]);
+
+impl Bezier {
+    /// the coefficients of a t³ + b t² + c t + d
+    fn coefficients(&self) -> [Vec2; 4] {
+        let [p0, p1, p2, p3] = self.0;
+        [-p0 + 3.0 * p1 - 3.0 * p2 + p3, 3.0 * p0 - 6.0 * p1 + 3.0 * p2, -3.0 * p0 + 3.0 * p1, p0]
+    }
+    /// the point at `t` from 0 at the start to 1 at the end
+    pub fn point(&self, t: f32) -> Vec2 {
+        let [a, b, c, d] = self.coefficients();
+        ((a * t + b) * t + c) * t + d
+    }
+    /// which way the curve goes at `t`, not normalized
+    pub fn tangent(&self, t: f32) -> Vec2 {
+        let [a, b, c, _] = self.coefficients();
+        (3.0 * a * t + 2.0 * b) * t + c
+    }
+    fn bend(&self, t: f32) -> Vec2 {
+        let [a, b, _, _] = self.coefficients();
+        6.0 * a * t + 2.0 * b
+    }
+    /// where a ray crosses the curve as (distance along the ray, t), nearest first. misses rays that only graze it.
+    /// t goes from 0 up to but not including 1, so curves joined end to end aren't crossed twice where they meet
+    ///
+    /// direction: should be normalized
+    pub fn ray_hits(&self, origin: Vec2, direction: Vec2) -> Vec<(f32, f32)> {
+        // how far the curve is to the side of the ray, it's crossed where that's zero
+        let side = |t: f32| direction.perp_dot(self.point(t) - origin);
+        let mut hits = vec![];
+        for step in 0..SEARCH_STEPS {
+            let (mut low, mut high) = (step as f32 / SEARCH_STEPS as f32, (step + 1) as f32 / SEARCH_STEPS as f32);
+            let (low_side, high_side) = (side(low), side(high));
+            if low_side == 0.0 {
+                hits.push(low);
+                continue;
+            }
+            if low_side * high_side >= 0.0 {
+                continue;
+            }
+            // bisect down to the precision of an f32
+            for _ in 0..24 {
+                let middle = (low + high) / 2.0;
+                if side(middle) * low_side > 0.0 {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+            hits.push((low + high) / 2.0);
+        }
+        let mut hits: Vec<(f32, f32)> = hits.into_iter().map(|t| (direction.dot(self.point(t) - origin), t)).collect();
+        hits.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        hits
+    }
+    /// the t of the point on the curve closest to `point`
+    pub fn closest(&self, point: Vec2) -> f32 {
+        let distance = |t: f32| self.point(t).distance_squared(point);
+        let mut t = (0..=SEARCH_STEPS)
+            .map(|step| step as f32 / SEARCH_STEPS as f32)
+            .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+            .unwrap_or(0.0);
+        // a few newton steps on where the distance stops changing
+        for _ in 0..4 {
+            let offset = self.point(t) - point;
+            let slope = offset.dot(self.tangent(t));
+            let change = self.tangent(t).length_squared() + offset.dot(self.bend(t));
+            if change <= 0.0 {
+                break;
+            }
+            t = (t - slope / change).clamp(0.0, 1.0);
+        }
+        t
+    }
+}
@@ -0,0 +1,53 @@
+use glam::Vec2;
+
+pub const MIN_ZOOM: f32 = 0.01;
+pub const MAX_ZOOM: f32 = 100.0;
+/// how much of the window "fit scene" fills
+const FIT_MARGIN: f32 = 0.9;
+
+/// maps between the world and the window, where the window's center is 0, 0
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    /// the world point in the middle of the window
+    pub position: Vec2,
+    /// window pixels per world unit
+    pub zoom: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            position: Vec2::ZERO,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Camera {
+    pub fn to_world(&self, screen: Vec2) -> Vec2 {
+        self.position + screen / self.zoom
+    }
+
+    pub fn to_screen(&self, world: Vec2) -> Vec2 {
+        (world - self.position) * self.zoom
+    }
+
+    /// zooms while keeping the world point under `screen` where it is
+    pub fn zoom_at(&mut self, screen: Vec2, factor: f32) {
+        let before = self.to_world(screen);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.position += before - self.to_world(screen);
+    }
+
+    /// moves the view with the mouse, so the world follows `screen_delta`
+    pub fn pan(&mut self, screen_delta: Vec2) {
+        self.position -= screen_delta / self.zoom;
+    }
+
+    /// shows everything between min and max
+    pub fn fit(&mut self, min: Vec2, max: Vec2, window_size: Vec2) {
+        let size = (max - min).max(Vec2::ONE);
+        self.position = (min + max) / 2.0;
+        self.zoom = ((window_size / size).min_element() * FIT_MARGIN).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+}
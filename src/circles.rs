@@ -1,10 +1,17 @@
-use nannou::{ease::circ, prelude::*};
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
 
-use crate::rays::Shape_Util;
-#[derive(Clone, Copy, Debug)]
+use crate::materials::Material;
+use crate::rays::{Hit, Shape_Util, EPSILON};
+use crate::transform::Transform;
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Circle {
     pub pos: Vec2,
     pub radius: f32,
+    #[serde(default)]
+    pub material: Material,
+    #[serde(default)]
+    pub transform: Transform,
 }
 
 impl Circle {
@@ -12,21 +19,29 @@ impl Circle {
         Self {
             pos: vec2(0.0, 0.0),
             radius: 0.1,
+            material: Material::Mirror,
+            transform: Transform::default(),
         }
     }
     pub fn from(pos: Vec2, radius: f32) -> Self {
-        Self { pos, radius }
+        Self {
+            pos,
+            radius,
+            material: Material::Mirror,
+            transform: Transform::default(),
+        }
     }
 }
 
 impl Circle {
-    pub fn normal(&self, point: Vec2) -> Vec2 {
-        self.pos - point
+    /// the center in the world
+    pub fn center(&self) -> Vec2 {
+        self.transform.to_world(self.pos)
     }
 }
 
 impl Shape_Util for Circle {
-    fn intersect(&self, point: &Vec2) -> bool {
+    fn local_intersect(&self, point: &Vec2) -> bool {
         let dx = self.pos.x - point.x;
         let dy = self.pos.y - point.y;
         let distance_squared = dx * dx + dy * dy;
@@ -37,4 +52,33 @@ impl Shape_Util for Circle {
     fn compare(&self, circle: &Circle) -> bool {
         self.pos == circle.pos && self.radius == circle.radius
     }
+    fn transform(&self) -> Transform {
+        self.transform
+    }
+    fn local_ray_intersect(&self, origin: Vec2, direction: Vec2) -> Option<Hit> {
+        let center_to_origin = origin - self.pos;
+        let b = center_to_origin.dot(direction);
+        let c = center_to_origin.length_squared() - self.radius * self.radius;
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        // take the near side unless it's behind the ray (ray starts inside the circle)
+        let root = discriminant.sqrt();
+        let mut distance = -b - root;
+        if distance <= EPSILON {
+            distance = -b + root;
+        }
+        if distance <= EPSILON {
+            return None;
+        }
+
+        let point = origin + direction * distance;
+        Some(Hit {
+            distance,
+            point,
+            normal: (point - self.pos) / self.radius,
+        })
+    }
 }
@@ -0,0 +1,143 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::lines::Line;
+use crate::materials::Material;
+use crate::rays::{Hit, Shape_Util};
+use crate::transform::Transform;
+
+/// how many pieces a detector's histogram has unless the scene file says otherwise
+pub const DETECTOR_BINS: usize = 32;
+
+fn detector_bins() -> usize {
+    DETECTOR_BINS
+}
+
+/// a screen from start to end that records every ray that hits it. it soaks them up unless
+/// its material says otherwise, a transparent one measures a beam without changing it
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Detector {
+    pub start: Vec2,
+    pub end: Vec2,
+    /// how many pieces the histogram splits it into
+    #[serde(default = "detector_bins")]
+    pub bins: usize,
+    #[serde(default = "Material::absorber")]
+    pub material: Material,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+/// where and how a ray hit a detector
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DetectorHit {
+    /// index of the detector in the scene's shapes
+    pub detector: usize,
+    /// how far from the detector's start, in the world
+    pub position: f32,
+    /// from the detector's normal in radians, positive when the ray goes towards the end
+    pub angle: f32,
+    pub power: f32,
+    /// in nanometers, None for plain light
+    pub wavelength: Option<f32>,
+}
+
+/// a summary of the hits on a detector
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Profile {
+    /// all the power that landed on it
+    pub power: f32,
+    /// where it landed on average, weighted by power
+    pub center: f32,
+    /// the root mean square distance from the center, weighted by power. small when it's focused
+    pub width: f32,
+}
+
+impl Detector {
+    pub fn new(start: Vec2, end: Vec2) -> Self {
+        Detector {
+            start,
+            end,
+            bins: DETECTOR_BINS,
+            material: Material::Absorber,
+            transform: Transform::default(),
+        }
+    }
+
+    /// how long it is in the world
+    pub fn length(&self) -> f32 {
+        self.start.distance(self.end) * self.transform.scale
+    }
+    /// the same segment as a line, in the detector's coordinates
+    fn line(&self) -> Line {
+        Line::from(self.start, self.end, 1.0)
+    }
+    /// the ends in the world
+    pub fn ends(&self) -> (Vec2, Vec2) {
+        (self.transform.to_world(self.start), self.transform.to_world(self.end))
+    }
+
+    /// what a detector records when a ray going in `direction` hits it at `hit`
+    pub fn record(&self, detector: usize, hit: &Hit, direction: Vec2, power: f32, wavelength: Option<f32>) -> DetectorHit {
+        let (start, end) = self.ends();
+        let along = (end - start).normalize_or_zero();
+        // the hit normal faces the ray, so the ray goes against it
+        let angle = direction.dot(along).atan2(-direction.dot(hit.normal));
+        DetectorHit {
+            detector,
+            position: (hit.point - start).dot(along),
+            angle,
+            power,
+            wavelength,
+        }
+    }
+
+    /// the power per unit length that landed on each of `bins` equal pieces from start to end,
+    /// the irradiance along the detector
+    pub fn histogram(&self, hits: &[DetectorHit]) -> Vec<f32> {
+        let bins = self.bins.max(1);
+        let length = self.length();
+        let mut histogram = vec![0.0; bins];
+        if length <= 0.0 {
+            return histogram;
+        }
+        let width = length / bins as f32;
+        for hit in hits {
+            let bin = ((hit.position / width) as usize).min(bins - 1);
+            histogram[bin] += hit.power / width;
+        }
+        histogram
+    }
+}
+
+impl Profile {
+    pub fn of(hits: &[DetectorHit]) -> Self {
+        let power: f32 = hits.iter().map(|hit| hit.power).sum();
+        if power <= 0.0 {
+            return Profile::default();
+        }
+        let center = hits.iter().map(|hit| hit.position * hit.power).sum::<f32>() / power;
+        let variance = hits.iter().map(|hit| (hit.position - center).powi(2) * hit.power).sum::<f32>() / power;
+        Profile {
+            power,
+            center,
+            width: variance.sqrt(),
+        }
+    }
+}
+
+impl Shape_Util for Detector {
+    fn compare(&self, detector: &Detector) -> bool {
+        self.start == detector.start && self.end == detector.end
+    }
+    fn transform(&self) -> Transform {
+        self.transform
+    }
+    fn local_intersect(&self, point: &Vec2) -> bool {
+        self.line().local_intersect(point)
+    }
+    /// like a line, the normal faces the incoming ray
+    fn local_ray_intersect(&self, origin: Vec2, direction: Vec2) -> Option<Hit> {
+        self.line().local_ray_intersect(origin, direction)
+    }
+}
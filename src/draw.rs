@@ -0,0 +1,193 @@
+use nannou::prelude::*;
+
+use crate::circles::Circle;
+use crate::color::Color;
+use crate::detectors::Detector;
+use crate::editor::Editor;
+use crate::lines::Line;
+use crate::materials::Material;
+use crate::mediums::Medium;
+use crate::mirrors::Mirror;
+use crate::rays::{Ray, Shape};
+use crate::scene::Scene;
+use crate::spectrum::wavelength_to_color;
+
+/// how thick a segment with a power of 1 is drawn
+const RAY_WEIGHT: f32 = 2.0;
+/// how many pieces a segment that loses power along the way is drawn in
+const FADE_STEPS: usize = 8;
+/// how far apart the marks on the back of one-sided shapes are
+const HATCH_SPACING: f32 = 8.0;
+
+/// lines and mirrors are black, fainter the more light goes through them
+fn surface_color(material: Material) -> Rgba {
+    match material {
+        Material::PartialMirror { reflectivity } => rgba(0.0, 0.0, 0.0, 0.25 + 0.75 * reflectivity.clamp(0.0, 1.0)),
+        Material::Transparent => rgba(0.0, 0.0, 0.0, 0.25),
+        _ => rgba(0.0, 0.0, 0.0, 1.0),
+    }
+}
+
+/// short marks on the back of a one-sided surface, along the points with the way the front faces at each
+fn hatch(draw: &Draw, points: &[(Vec2, Vec2)]) {
+    let mut along = 0.0;
+    for piece in points.windows(2) {
+        let (start, end) = (piece[0].0, piece[1].0);
+        let length = start.distance(end);
+        while along <= length {
+            let t = if length > 0.0 { along / length } else { 0.0 };
+            let point = start.lerp(end, t);
+            let back = -piece[0].1.lerp(piece[1].1, t).normalize_or_zero();
+            draw.line()
+                .start(point)
+                .end(point + (back + (end - start).normalize_or_zero()) * HATCH_SPACING * 0.6)
+                .weight(1.0)
+                .color(GRAY);
+            along += HATCH_SPACING;
+        }
+        along -= length;
+    }
+}
+
+pub fn to_rgba(color: Color) -> Rgba {
+    rgba(color.red, color.green, color.blue, color.alpha)
+}
+
+impl Ray {
+    pub fn show(&self, draw: &Draw) {
+        // plain light is black, the rest in the color of its wavelength
+        let color = self.wavelength.map_or(Color::new(0.0, 0.0, 0.0, 1.0), wavelength_to_color);
+        // weaker parts of the ray are thinner and fainter
+        let line = |start: Vec2, end: Vec2, power: f32| {
+            let strength = power.clamp(0.0, 1.0);
+            draw.line()
+                .start(start)
+                .end(end)
+                .weight(RAY_WEIGHT * strength.sqrt().max(0.25))
+                .color(rgba(color.red, color.green, color.blue, strength));
+        };
+        for segment in self.segments() {
+            if segment.end_power == segment.power {
+                line(segment.start, segment.end, segment.power);
+                continue;
+            }
+            // fades out inside an absorbing medium
+            for step in 0..FADE_STEPS {
+                let from = step as f32 / FADE_STEPS as f32;
+                let to = (step + 1) as f32 / FADE_STEPS as f32;
+                let power = segment.power + (segment.end_power - segment.power) * (from + to) / 2.0;
+                line(segment.start.lerp(segment.end, from), segment.start.lerp(segment.end, to), power);
+            }
+        }
+    }
+}
+
+impl Line {
+    pub fn show(&self, draw: &Draw) {
+        let line = self.to_world();
+        draw.line()
+            .start(line.start)
+            .end(line.end)
+            .weight(self.thickness * self.transform.scale)
+            .color(surface_color(self.material));
+        if self.one_sided {
+            let front = line.normal();
+            hatch(draw, &[(line.start, front), (line.end, front)]);
+        }
+    }
+}
+
+impl Circle {
+    pub fn show(&self, draw: &Draw) {
+        let center = self.center();
+        draw.ellipse()
+            .x_y(center.x, center.y)
+            .radius(self.radius * self.transform.scale)
+            .color(BLACK);
+    }
+}
+
+impl Medium {
+    pub fn show(&self, draw: &Draw) {
+        draw.polygon()
+            .points(self.outline())
+            .color(to_rgba(self.color()));
+    }
+}
+
+impl Mirror {
+    pub fn show(&self, draw: &Draw) {
+        draw.polyline()
+            .weight(self.thickness * self.transform.scale)
+            .points(self.outline())
+            .color(surface_color(self.material));
+        if self.one_sided {
+            let points: Vec<(Vec2, Vec2)> = self.outline().into_iter().map(|point| (point, self.front(point))).collect();
+            hatch(draw, &points);
+        }
+    }
+}
+
+impl Detector {
+    pub fn show(&self, draw: &Draw) {
+        let (start, end) = self.ends();
+        draw.line()
+            .start(start)
+            .end(end)
+            .weight(3.0 * self.transform.scale)
+            .color(rgba(0.1, 0.6, 0.2, 1.0));
+        // ticks across the ends, so it reads as a screen
+        let across = (end - start).perp().normalize_or_zero() * 6.0 * self.transform.scale;
+        for point in [start, end] {
+            draw.line()
+                .start(point - across)
+                .end(point + across)
+                .weight(2.0)
+                .color(rgba(0.1, 0.6, 0.2, 1.0));
+        }
+    }
+}
+
+impl Shape {
+    pub fn show(&self, draw: &Draw) {
+        match self {
+            Shape::Line(line) => line.show(draw),
+            Shape::Circle(circle) => circle.show(draw),
+            Shape::Medium(medium) => medium.show(draw),
+            Shape::Mirror(mirror) => mirror.show(draw),
+            Shape::Detector(detector) => detector.show(draw),
+        }
+    }
+}
+
+impl Scene {
+    pub fn show(&self, draw: &Draw) {
+        for shape in &self.shapes {
+            shape.show(draw);
+        }
+    }
+}
+
+impl Editor {
+    /// draws the handles of the selected shape
+    pub fn show(&self, draw: &Draw, shapes: &[Shape]) {
+        let Some(shape) = self.selected.and_then(|selected| shapes.get(selected)) else {
+            return;
+        };
+        let handles = shape.handles();
+        // a thin arm out to the rotation handle
+        if let Some(rotation) = handles.last() {
+            draw.line()
+                .start(shape.pivot())
+                .end(*rotation)
+                .weight(self.grab_distance / 8.0)
+                .color(ORANGE);
+        }
+        for handle in handles {
+            draw.ellipse()
+                .x_y(handle.x, handle.y)
+                .radius(self.grab_distance / 2.0)
+                .color(ORANGE);
+        }
+    }
+}
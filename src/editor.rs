@@ -0,0 +1,323 @@
+use std::f32::consts::TAU;
+
+use glam::{vec2, Vec2};
+
+use crate::geometry::{closest_point_on_segment, distance_to_segment, segment_intersection};
+use crate::mediums::{Side, ARC_STEPS};
+use crate::mirrors::MirrorCurve;
+use crate::rays::{Shape, Shape_Util};
+
+/// how close the mouse has to be to grab a handle or a line
+pub const GRAB_DISTANCE: f32 = 8.0;
+/// how far past the furthest handle the rotation handle sits
+pub const ROTATION_HANDLE_GAP: f32 = 30.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Drag {
+    /// moving one handle of the selected shape
+    Handle(usize),
+    /// moving the whole selected shape, holds where the mouse was last
+    Shape(Vec2),
+}
+
+/// selects shapes and drags their handles with the mouse
+#[derive(Clone, Debug)]
+pub struct Editor {
+    /// index into the scene's shapes
+    pub selected: Option<usize>,
+    /// how close to grab in world units. set it to GRAB_DISTANCE / zoom to keep it the same on screen
+    pub grab_distance: f32,
+    /// dragged handles stick to the handles and sides of other shapes when they come within grab distance
+    pub snap: bool,
+    drag: Option<Drag>,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Editor {
+            selected: None,
+            grab_distance: GRAB_DISTANCE,
+            snap: false,
+            drag: None,
+        }
+    }
+}
+
+impl Editor {
+    /// selects the shape under the point and starts dragging it or one of its handles
+    pub fn press(&mut self, shapes: &[Shape], point: Vec2) {
+        // the selected shape's handles win, they can sit on top of other shapes
+        let selected_handle = self
+            .selected
+            .and_then(|selected| shapes.get(selected))
+            .and_then(|shape| grabbed_handle(shape, point, self.grab_distance));
+        if let Some(handle) = selected_handle {
+            self.drag = Some(Drag::Handle(handle));
+            return;
+        }
+
+        // last shape is drawn on top, so it gets picked first
+        self.selected = shapes.iter().rposition(|shape| shape.is_under(point, self.grab_distance));
+        self.drag = self.selected.map(|selected| match grabbed_handle(&shapes[selected], point, self.grab_distance) {
+            Some(handle) => Drag::Handle(handle),
+            None => Drag::Shape(point),
+        });
+    }
+
+    pub fn drag(&mut self, shapes: &mut [Shape], point: Vec2) {
+        let (Some(selected), Some(drag)) = (self.selected, self.drag) else {
+            return;
+        };
+        // the rotation handle doesn't snap, it only sets an angle
+        let point = match (drag, shapes.get(selected)) {
+            (Drag::Handle(handle), Some(shape)) if self.snap && handle + 1 < shape.handles().len() => {
+                snap(shapes, selected, point, self.grab_distance)
+            }
+            _ => point,
+        };
+        let Some(shape) = shapes.get_mut(selected) else {
+            return;
+        };
+        match drag {
+            Drag::Handle(handle) => shape.move_handle(handle, point),
+            Drag::Shape(last) => {
+                shape.translate(point - last);
+                self.drag = Some(Drag::Shape(point));
+            }
+        }
+    }
+
+    pub fn release(&mut self) {
+        self.drag = None;
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// adds a shape and selects it
+    pub fn add(&mut self, shapes: &mut Vec<Shape>, shape: Shape) {
+        shapes.push(shape);
+        self.selected = Some(shapes.len() - 1);
+        self.drag = None;
+    }
+
+    pub fn delete_selected(&mut self, shapes: &mut Vec<Shape>) {
+        if let Some(selected) = self.selected.take() {
+            if selected < shapes.len() {
+                shapes.remove(selected);
+            }
+        }
+        self.drag = None;
+    }
+}
+
+fn closest_handle(shape: &Shape, point: Vec2) -> Option<usize> {
+    shape
+        .handles()
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.distance(point).total_cmp(&b.distance(point)))
+        .map(|(index, _)| index)
+}
+
+fn grabbed_handle(shape: &Shape, point: Vec2, grab_distance: f32) -> Option<usize> {
+    closest_handle(shape, point).filter(|&handle| shape.handles()[handle].distance(point) <= grab_distance)
+}
+
+/// the closest handle of another shape within `distance`, or else the closest place where the sides
+/// of two of them cross, or else the closest point on one of their sides. the point itself when
+/// nothing is that close
+fn snap(shapes: &[Shape], skip: usize, point: Vec2, distance: f32) -> Vec2 {
+    let others = shapes.iter().enumerate().filter(|&(index, _)| index != skip);
+    let closest = |candidates: Vec<Vec2>| {
+        candidates
+            .into_iter()
+            .filter(|candidate| candidate.distance(point) <= distance)
+            .min_by(|a, b| a.distance(point).total_cmp(&b.distance(point)))
+    };
+
+    let handles = others
+        .clone()
+        .flat_map(|(_, shape)| {
+            let mut handles = shape.handles();
+            handles.pop();
+            handles
+        })
+        .collect();
+    // only the sides close enough to matter, with the shape they belong to
+    let sides: Vec<(usize, Vec2, Vec2)> = others
+        .flat_map(|(index, shape)| shape.sides().into_iter().map(move |(start, end)| (index, start, end)))
+        .filter(|&(_, start, end)| distance_to_segment(start, end, point) <= distance)
+        .collect();
+    // like the corner two mirrors make. pieces of the same curve meet at their ends, so those don't count
+    let crossings = sides
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &(a, a_start, a_end))| {
+            sides[i + 1..]
+                .iter()
+                .filter(move |&&(b, _, _)| b != a)
+                .filter_map(move |&(_, b_start, b_end)| segment_intersection(a_start, a_end, b_start, b_end))
+        })
+        .collect();
+    let on_sides = sides.iter().map(|&(_, start, end)| closest_point_on_segment(start, end, point)).collect();
+    closest(handles).or_else(|| closest(crossings)).or_else(|| closest(on_sides)).unwrap_or(point)
+}
+
+impl Shape {
+    /// the outline of the shape as straight pieces in the world, curves are split up
+    pub fn sides(&self) -> Vec<(Vec2, Vec2)> {
+        let pieces = |points: &[Vec2]| points.windows(2).map(|piece| (piece[0], piece[1])).collect();
+        match self {
+            Shape::Line(line) => {
+                let line = line.to_world();
+                vec![(line.start, line.end)]
+            }
+            Shape::Circle(circle) => {
+                let radius = circle.radius * circle.transform.scale;
+                let points: Vec<Vec2> = (0..=ARC_STEPS)
+                    .map(|step| {
+                        let angle = step as f32 / ARC_STEPS as f32 * TAU;
+                        circle.center() + vec2(angle.cos(), angle.sin()) * radius
+                    })
+                    .collect();
+                pieces(&points)
+            }
+            Shape::Medium(medium) => {
+                let mut points = medium.outline();
+                points.extend(points.first().copied());
+                pieces(&points)
+            }
+            Shape::Mirror(mirror) => pieces(&mirror.outline()),
+            Shape::Detector(detector) => vec![detector.ends()],
+        }
+    }
+
+    /// the point in the world the shape turns around
+    pub fn pivot(&self) -> Vec2 {
+        match self {
+            Shape::Line(line) => line.transform.to_world((line.start + line.end) / 2.0),
+            Shape::Circle(circle) => circle.center(),
+            Shape::Medium(medium) => medium.transform.to_world(medium.center()),
+            Shape::Mirror(mirror) => mirror.transform.translation,
+            Shape::Detector(detector) => detector.transform.to_world((detector.start + detector.end) / 2.0),
+        }
+    }
+
+    /// points in the world that can be dragged to change the shape
+    ///
+    /// line and detector: start, end. circle: center, a point on the edge. medium: its points, then the control points
+    /// of its Bézier sides. arc: its ends. parabola: the focus and the top end. ellipse: its ends and
+    /// the corner of the box around it. Bézier mirror: its points.
+    /// the last one is the rotation handle, it sits out from the pivot in the direction the shape is turned
+    pub fn handles(&self) -> Vec<Vec2> {
+        let transform = self.transform();
+        let mut handles: Vec<Vec2> = match self {
+            Shape::Line(line) => vec![line.start, line.end],
+            Shape::Detector(detector) => vec![detector.start, detector.end],
+            Shape::Circle(circle) => vec![circle.pos, circle.pos + vec2(circle.radius, 0.0)],
+            Shape::Medium(medium) => {
+                let controls = medium.sides.iter().flat_map(|side| match side {
+                    Side::Bezier { controls } => controls.to_vec(),
+                    _ => vec![],
+                });
+                medium.points.iter().copied().chain(controls).collect()
+            }
+            Shape::Mirror(mirror) => match mirror.curve {
+                MirrorCurve::Arc { .. } => vec![mirror.local_point(0.0), mirror.local_point(1.0)],
+                MirrorCurve::Parabola { focal_length, .. } => vec![vec2(focal_length, 0.0), mirror.local_point(1.0)],
+                MirrorCurve::Ellipse { radii, .. } => vec![mirror.local_point(0.0), mirror.local_point(1.0), radii],
+                MirrorCurve::Bezier { points } => points.to_vec(),
+            },
+        }
+        .into_iter()
+        .map(|handle| transform.to_world(handle))
+        .collect();
+
+        let pivot = self.pivot();
+        let reach = handles.iter().map(|handle| handle.distance(pivot)).fold(0.0, f32::max);
+        let (sin, cos) = transform.rotation.sin_cos();
+        handles.push(pivot + vec2(cos, sin) * (reach + ROTATION_HANDLE_GAP));
+        handles
+    }
+
+    /// to: in the world
+    pub fn move_handle(&mut self, handle: usize, to: Vec2) {
+        if handle + 1 == self.handles().len() {
+            let pivot = self.pivot();
+            let transform = self.transform_mut();
+            let angle = (to.y - pivot.y).atan2(to.x - pivot.x);
+            transform.rotate_about(pivot, angle - transform.rotation);
+            return;
+        }
+        let to = self.transform().to_local(to);
+        match self {
+            Shape::Line(line) => match handle {
+                0 => line.start = to,
+                _ => line.end = to,
+            },
+            Shape::Detector(detector) => match handle {
+                0 => detector.start = to,
+                _ => detector.end = to,
+            },
+            Shape::Circle(circle) => match handle {
+                0 => circle.pos = to,
+                _ => circle.radius = circle.pos.distance(to).max(1.0),
+            },
+            Shape::Medium(medium) => {
+                let count = medium.points.len();
+                if let Some(point) = medium.points.get_mut(handle) {
+                    *point = to;
+                    return;
+                }
+                let control = medium
+                    .sides
+                    .iter_mut()
+                    .flat_map(|side| match side {
+                        Side::Bezier { controls } => controls.iter_mut().collect(),
+                        _ => vec![],
+                    })
+                    .nth(handle - count);
+                if let Some(control) = control {
+                    *control = to;
+                }
+            }
+            Shape::Mirror(mirror) => match (&mut mirror.curve, handle) {
+                (MirrorCurve::Arc { radius, start, .. }, 0) => {
+                    *radius = to.length().max(1.0);
+                    *start = to.y.atan2(to.x);
+                }
+                (MirrorCurve::Arc { radius, end, .. }, _) => {
+                    *radius = to.length().max(1.0);
+                    *end = to.y.atan2(to.x);
+                }
+                (MirrorCurve::Parabola { focal_length, .. }, 0) => *focal_length = to.x.max(1.0),
+                (MirrorCurve::Parabola { aperture, .. }, _) => *aperture = (to.y.abs() * 2.0).max(1.0),
+                (MirrorCurve::Ellipse { radii, start, .. }, 0) => *start = (to.y / radii.y).atan2(to.x / radii.x),
+                (MirrorCurve::Ellipse { radii, end, .. }, 1) => *end = (to.y / radii.y).atan2(to.x / radii.x),
+                (MirrorCurve::Ellipse { radii, .. }, _) => *radii = to.abs().max(Vec2::ONE),
+                (MirrorCurve::Bezier { points }, _) => {
+                    if let Some(point) = points.get_mut(handle) {
+                        *point = to;
+                    }
+                }
+            },
+        }
+    }
+
+    pub fn translate(&mut self, by: Vec2) {
+        self.transform_mut().translation += by;
+    }
+
+    /// if clicking on the point should select the shape
+    pub fn is_under(&self, point: Vec2, grab_distance: f32) -> bool {
+        match self {
+            Shape::Circle(circle) => circle.intersect(&point),
+            Shape::Medium(medium) => medium.intersect(&point),
+            Shape::Line(_) | Shape::Mirror(_) | Shape::Detector(_) => {
+                self.sides().iter().any(|&(start, end)| distance_to_segment(start, end, point) <= grab_distance)
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! small pieces of 2d geometry shared by the tracer, the shapes and the editor
+
+use glam::Vec2;
+
+/// the point of the segment from start to end closest to `point`
+pub fn closest_point_on_segment(start: Vec2, end: Vec2, point: Vec2) -> Vec2 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared == 0.0 {
+        return start;
+    }
+    let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+    start + segment * t
+}
+
+/// how far a point is from the closest point of the segment from start to end
+pub fn distance_to_segment(start: Vec2, end: Vec2, point: Vec2) -> f32 {
+    closest_point_on_segment(start, end, point).distance(point)
+}
+
+/// which side of the line through start and end a point is on: positive on the left going from start
+/// to end, negative on the right and zero on the line. it's the distance times the length of the segment
+pub fn side(start: Vec2, end: Vec2, point: Vec2) -> f32 {
+    (end - start).perp_dot(point - start)
+}
+
+/// where a ray crosses the segment from start to end, as the distance along the ray. it can be negative
+/// when the segment is behind the ray. None when they don't cross or are parallel
+///
+/// direction: should be normalized for the distance to be a length
+pub fn ray_segment_intersection(origin: Vec2, direction: Vec2, start: Vec2, end: Vec2) -> Option<f32> {
+    let segment = end - start;
+    let denominator = direction.perp_dot(segment);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let offset = start - origin;
+    let along = offset.perp_dot(direction) / denominator;
+    if !(0.0..=1.0).contains(&along) {
+        return None;
+    }
+    Some(offset.perp_dot(segment) / denominator)
+}
+
+/// where two segments cross, None when they don't or are parallel
+pub fn segment_intersection(a_start: Vec2, a_end: Vec2, b_start: Vec2, b_end: Vec2) -> Option<Vec2> {
+    let a = a_end - a_start;
+    let distance = ray_segment_intersection(a_start, a, b_start, b_end)?;
+    // the ray wasn't normalized, so the distance is the fraction of the way along a
+    (0.0..=1.0).contains(&distance).then(|| a_start + a * distance)
+}
@@ -0,0 +1,103 @@
+use crate::lights::Light;
+use crate::scene::Scene;
+
+/// how many steps can be undone
+pub const HISTORY_LIMIT: usize = 100;
+
+/// undo and redo by keeping copies of the scene.
+///
+/// call `update` every frame, it notices changes by itself so every kind of edit can be undone
+#[derive(Clone, Debug)]
+pub struct History {
+    undo: Vec<Scene>,
+    redo: Vec<Scene>,
+    /// the scene as of the last step
+    saved: Scene,
+}
+
+/// the light is aimed at the mouse all the time, that isn't an edit. runs every frame, so it compares
+/// field by field instead of copying the scene. taken apart so a new field can't be forgotten
+fn differs(a: &Scene, b: &Scene) -> bool {
+    let Scene {
+        shapes,
+        ambient_index,
+        light,
+        bounces,
+        max_length,
+        fresnel,
+        min_power,
+        grin_step,
+        diffuse_rays,
+    } = b;
+    let light = Light {
+        direction: a.light.direction,
+        ..*light
+    };
+    a.shapes != *shapes
+        || a.ambient_index != *ambient_index
+        || a.light != light
+        || a.bounces != *bounces
+        || a.max_length != *max_length
+        || a.fresnel != *fresnel
+        || a.min_power != *min_power
+        || a.grin_step != *grin_step
+        || a.diffuse_rays != *diffuse_rays
+}
+
+impl History {
+    pub fn new(scene: &Scene) -> Self {
+        History {
+            undo: vec![],
+            redo: vec![],
+            saved: scene.clone(),
+        }
+    }
+
+    /// busy: an edit is still going on (like a drag). it becomes one step once it's done
+    pub fn update(&mut self, scene: &Scene, busy: bool) {
+        if !busy {
+            self.commit(scene);
+        }
+    }
+
+    fn commit(&mut self, scene: &Scene) {
+        if !differs(&self.saved, scene) {
+            return;
+        }
+        self.undo.push(std::mem::replace(&mut self.saved, scene.clone()));
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self, scene: &mut Scene) {
+        self.commit(scene);
+        if let Some(previous) = self.undo.pop() {
+            self.redo.push(std::mem::replace(&mut self.saved, previous));
+            self.restore(scene);
+        }
+    }
+
+    pub fn redo(&mut self, scene: &mut Scene) {
+        self.commit(scene);
+        if let Some(next) = self.redo.pop() {
+            self.undo.push(std::mem::replace(&mut self.saved, next));
+            self.restore(scene);
+        }
+    }
+
+    fn restore(&self, scene: &mut Scene) {
+        let direction = scene.light.direction;
+        *scene = self.saved.clone();
+        scene.light.direction = direction;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
@@ -0,0 +1,422 @@
+use nannou::prelude::*;
+use nannou_egui::egui;
+use nannou_egui::egui::plot::{Line as PlotLine, Plot, Value, Values};
+
+use ray_tracing::detectors::{Detector, DetectorHit, Profile};
+use ray_tracing::materials::Material;
+use ray_tracing::mediums::{self, Absorption, Gradient, RefractiveIndex, Side};
+use ray_tracing::mirrors::MirrorCurve;
+use ray_tracing::rays::Shape;
+use ray_tracing::spectrum::REFERENCE_WAVELENGTH;
+use ray_tracing::transform::Transform;
+
+fn shape_name(shape: &Shape) -> &'static str {
+    match shape {
+        Shape::Line(line) if matches!(line.material, Material::PartialMirror { .. }) => "Beam splitter",
+        Shape::Line(_) => "Line",
+        Shape::Circle(_) => "Circle",
+        Shape::Medium(_) => "Medium",
+        Shape::Mirror(mirror) => match mirror.curve {
+            MirrorCurve::Arc { .. } => "Arc mirror",
+            MirrorCurve::Parabola { .. } => "Parabolic mirror",
+            MirrorCurve::Ellipse { .. } => "Elliptical mirror",
+            MirrorCurve::Bezier { .. } => "Bézier mirror",
+        },
+        Shape::Detector(_) => "Detector",
+    }
+}
+
+/// the shapes of the scene to pick from
+pub fn shape_list(ui: &mut egui::Ui, shapes: &[Shape], selected: &mut Option<usize>) {
+    egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+        for (index, shape) in shapes.iter().enumerate() {
+            ui.selectable_value(selected, Some(index), format!("{}: {}", index, shape_name(shape)));
+        }
+    });
+}
+
+fn vec2_edit(ui: &mut egui::Ui, label: &str, value: &mut Vec2) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(egui::DragValue::new(&mut value.x).prefix("x: "));
+        ui.add(egui::DragValue::new(&mut value.y).prefix("y: "));
+    });
+}
+
+/// straight, an arc with a radius (negative bends it into the medium) or a Bézier curve with two
+/// control points. start, end: the points the side joins, new control points go a third of the way along
+fn side_edit(ui: &mut egui::Ui, index: usize, side: &mut Side, start: Vec2, end: Vec2) {
+    let name = match side {
+        Side::Straight => "straight",
+        Side::Arc { .. } => "arc",
+        Side::Bezier { .. } => "Bézier",
+    };
+    let options = [
+        ("straight", Side::Straight),
+        ("arc", Side::Arc { radius: 100.0 }),
+        ("Bézier", Side::Bezier { controls: [start.lerp(end, 1.0 / 3.0), start.lerp(end, 2.0 / 3.0)] }),
+    ];
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source(("side", index)).selected_text(name).show_ui(ui, |ui| {
+            for (option, value) in options {
+                if ui.selectable_label(option == name, option).clicked() && option != name {
+                    *side = value;
+                }
+            }
+        });
+        match side {
+            Side::Straight => {}
+            Side::Arc { radius } => {
+                ui.add(egui::DragValue::new(radius).prefix("radius: "));
+            }
+            Side::Bezier { controls } => {
+                for control in controls.iter_mut() {
+                    ui.add(egui::DragValue::new(&mut control.x).prefix("x: "));
+                    ui.add(egui::DragValue::new(&mut control.y).prefix("y: "));
+                }
+            }
+        }
+    });
+}
+
+/// where the shape is, how far it's turned in degrees and how big it is
+fn transform_edit(ui: &mut egui::Ui, transform: &mut Transform) {
+    vec2_edit(ui, "position", &mut transform.translation);
+    let mut degrees = transform.rotation.to_degrees();
+    if ui.add(egui::Slider::new(&mut degrees, -180.0..=180.0).text("rotation")).changed() {
+        transform.rotation = degrees.to_radians();
+    }
+    ui.add(egui::Slider::new(&mut transform.scale, 0.1..=10.0).logarithmic(true).text("scale"));
+}
+
+fn angle_edit(ui: &mut egui::Ui, label: &str, radians: &mut f32) {
+    let mut degrees = radians.to_degrees();
+    if ui.add(egui::Slider::new(&mut degrees, -360.0..=360.0).text(label)).changed() {
+        *radians = degrees.to_radians();
+    }
+}
+
+/// picks the kind of curve and edits its size. switching kind starts from a curve of about the same size
+fn curve_edit(ui: &mut egui::Ui, curve: &mut MirrorCurve) {
+    let size = match *curve {
+        MirrorCurve::Arc { radius, .. } => radius,
+        MirrorCurve::Parabola { aperture, .. } => aperture / 2.0,
+        MirrorCurve::Ellipse { radii, .. } => radii.max_element(),
+        MirrorCurve::Bezier { points } => points[0].distance(points[3]) / 2.0,
+    };
+    let options = [
+        ("arc", MirrorCurve::Arc { radius: size, start: -PI / 4.0, end: PI / 4.0 }),
+        ("parabola", MirrorCurve::Parabola { focal_length: size / 2.0, aperture: size * 2.0 }),
+        ("ellipse", MirrorCurve::Ellipse { radii: vec2(size, size * 0.6), start: -PI / 2.0, end: PI / 2.0 }),
+        (
+            "Bézier",
+            MirrorCurve::Bezier { points: [vec2(-size, 0.0), vec2(-size / 3.0, size), vec2(size / 3.0, -size), vec2(size, 0.0)] },
+        ),
+    ];
+    let current = options.iter().position(|(_, option)| std::mem::discriminant(option) == std::mem::discriminant(curve)).unwrap_or(0);
+    egui::ComboBox::from_label("curve").selected_text(options[current].0).show_ui(ui, |ui| {
+        for (index, (name, option)) in options.iter().enumerate() {
+            if ui.selectable_label(index == current, *name).clicked() && index != current {
+                *curve = *option;
+            }
+        }
+    });
+
+    match curve {
+        MirrorCurve::Arc { radius, start, end } => {
+            ui.add(egui::Slider::new(radius, 1.0..=1000.0).text("radius"));
+            angle_edit(ui, "start", start);
+            angle_edit(ui, "end", end);
+        }
+        MirrorCurve::Parabola { focal_length, aperture } => {
+            ui.add(egui::Slider::new(focal_length, 1.0..=1000.0).text("focal length"));
+            ui.add(egui::Slider::new(aperture, 1.0..=1000.0).text("aperture"));
+        }
+        MirrorCurve::Ellipse { radii, start, end } => {
+            ui.add(egui::Slider::new(&mut radii.x, 1.0..=1000.0).text("x radius"));
+            ui.add(egui::Slider::new(&mut radii.y, 1.0..=1000.0).text("y radius"));
+            angle_edit(ui, "start", start);
+            angle_edit(ui, "end", end);
+        }
+        MirrorCurve::Bezier { points } => {
+            for (index, point) in points.iter_mut().enumerate() {
+                vec2_edit(ui, &format!("point {}", index), point);
+            }
+        }
+    }
+}
+
+/// what lines, circles and mirrors can be made of
+const SURFACES: [Material; 5] = [
+    Material::Mirror,
+    Material::Absorber,
+    Material::Diffuse,
+    Material::PartialMirror { reflectivity: 0.5 },
+    Material::Transparent,
+];
+
+fn material_name(material: &Material) -> &'static str {
+    match material {
+        Material::Mirror => "mirror",
+        Material::Absorber => "absorber",
+        Material::Refractive => "refractive",
+        Material::Diffuse => "diffuse",
+        Material::PartialMirror { .. } => "partial mirror",
+        Material::Transparent => "transparent",
+    }
+}
+
+fn material_edit(ui: &mut egui::Ui, material: &mut Material, options: &[Material]) {
+    egui::ComboBox::from_label("material")
+        .selected_text(material_name(material))
+        .show_ui(ui, |ui| {
+            for option in options {
+                // picking the same kind again keeps its reflectivity
+                let selected = material_name(material) == material_name(option);
+                if ui.selectable_label(selected, material_name(option)).clicked() && !selected {
+                    *material = *option;
+                }
+            }
+        });
+    if let Material::PartialMirror { reflectivity } = material {
+        ui.add(egui::Slider::new(reflectivity, 0.0..=1.0).text("reflectivity"));
+    }
+}
+
+fn dispersion_name(refractive_index: &RefractiveIndex) -> &'static str {
+    match refractive_index {
+        RefractiveIndex::Constant(_) => "none",
+        RefractiveIndex::Cauchy { .. } => "Cauchy",
+        RefractiveIndex::Sellmeier { .. } => "Sellmeier",
+    }
+}
+
+/// the irradiance along a detector as a histogram, with where the light landed and how spread out it is
+pub fn detector_plot(ui: &mut egui::Ui, index: usize, detector: &Detector, hits: &[DetectorHit]) {
+    let histogram = detector.histogram(hits);
+    let width = detector.length() / histogram.len() as f32;
+    // a step for each bin
+    let steps: Vec<Value> = histogram
+        .iter()
+        .enumerate()
+        .flat_map(|(bin, &irradiance)| {
+            let from = bin as f64 * width as f64;
+            [Value::new(from, irradiance), Value::new(from + width as f64, irradiance)]
+        })
+        .collect();
+    let plot = Plot::new(("detector", index))
+        .line(PlotLine::new(Values::from_values(steps)).name("power per length"))
+        .height(120.0)
+        .include_y(0.0);
+    ui.add(plot);
+    let profile = Profile::of(hits);
+    ui.label(format!(
+        "{} hits, power {:.3}, center {:.1}, width {:.1}",
+        hits.len(),
+        profile.power,
+        profile.center,
+        profile.width
+    ));
+}
+
+/// the refractive index and how it changes with the wavelength
+fn index_edit(ui: &mut egui::Ui, refractive_index: &mut RefractiveIndex) {
+    let reference = refractive_index.at(REFERENCE_WAVELENGTH);
+    // switching keeps about the same index, sellmeier starts as crown glass
+    let choices = [
+        RefractiveIndex::Constant(reference),
+        RefractiveIndex::Cauchy {
+            a: reference - 0.0042 / (REFERENCE_WAVELENGTH / 1000.0).powi(2),
+            b: 0.0042,
+        },
+        mediums::CROWN_GLASS,
+    ];
+    egui::ComboBox::from_label("dispersion")
+        .selected_text(dispersion_name(refractive_index))
+        .show_ui(ui, |ui| {
+            for choice in choices {
+                let selected = dispersion_name(refractive_index) == dispersion_name(&choice);
+                if ui.selectable_label(selected, dispersion_name(&choice)).clicked() && !selected {
+                    *refractive_index = choice;
+                }
+            }
+        });
+
+    match refractive_index {
+        RefractiveIndex::Constant(index) => {
+            ui.add(egui::Slider::new(index, 1.0..=3.0).text("refractive index"));
+        }
+        RefractiveIndex::Cauchy { a, b } => {
+            ui.add(egui::Slider::new(a, 1.0..=3.0).text("a"));
+            ui.add(egui::DragValue::new(b).speed(0.0001).prefix("b (µm²): "));
+        }
+        RefractiveIndex::Sellmeier { b, c } => {
+            for (i, (b, c)) in b.iter_mut().zip(c.iter_mut()).enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(b).speed(0.001).prefix(format!("b{}: ", i + 1)));
+                    ui.add(egui::DragValue::new(c).speed(0.001).prefix(format!("c{} (µm²): ", i + 1)));
+                });
+            }
+        }
+    }
+    ui.horizontal(|ui| {
+        for (name, index) in [("air", mediums::AIR), ("water", mediums::WATER), ("glass", mediums::GLASS), ("diamond", mediums::DIAMOND)] {
+            if ui.button(name).clicked() {
+                *refractive_index = index.into();
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        for (name, index) in [("crown glass", mediums::CROWN_GLASS), ("flint glass", mediums::FLINT_GLASS)] {
+            if ui.button(name).clicked() {
+                *refractive_index = index;
+            }
+        }
+    });
+    ui.label(format!("n = {:.4} at {} nm", refractive_index.at(REFERENCE_WAVELENGTH), REFERENCE_WAVELENGTH));
+}
+
+/// how much the medium soaks up, the same for every color or for each one
+fn absorption_edit(ui: &mut egui::Ui, absorption: &mut Absorption) {
+    let mut per_color = matches!(absorption, Absorption::Colors { .. });
+    if ui.checkbox(&mut per_color, "absorption per color").changed() {
+        *absorption = match *absorption {
+            Absorption::Constant(coefficient) => Absorption::Colors {
+                blue: coefficient,
+                green: coefficient,
+                red: coefficient,
+            },
+            Absorption::Colors { green, .. } => Absorption::Constant(green),
+        };
+    }
+    match absorption {
+        Absorption::Constant(coefficient) => {
+            ui.add(egui::Slider::new(coefficient, 0.0..=0.05).text("absorption"));
+        }
+        Absorption::Colors { blue, green, red } => {
+            ui.add(egui::Slider::new(blue, 0.0..=0.05).text("blue absorption"));
+            ui.add(egui::Slider::new(green, 0.0..=0.05).text("green absorption"));
+            ui.add(egui::Slider::new(red, 0.0..=0.05).text("red absorption"));
+        }
+    }
+}
+
+fn gradient_name(gradient: &Gradient) -> &'static str {
+    match gradient {
+        Gradient::None => "none",
+        Gradient::Linear { .. } => "linear",
+        Gradient::Radial { .. } => "radial",
+        Gradient::Custom(_) => "custom",
+    }
+}
+
+/// how the index changes over the medium
+fn gradient_edit(ui: &mut egui::Ui, gradient: &mut Gradient) {
+    let choices = [
+        Gradient::None,
+        Gradient::Linear { slope: vec2(0.0, 0.001) },
+        Gradient::Radial { change: -0.2, radius: 100.0 },
+    ];
+    egui::ComboBox::from_label("gradient")
+        .selected_text(gradient_name(gradient))
+        .show_ui(ui, |ui| {
+            for choice in choices {
+                let selected = gradient_name(gradient) == gradient_name(&choice);
+                if ui.selectable_label(selected, gradient_name(&choice)).clicked() && !selected {
+                    *gradient = choice;
+                }
+            }
+        });
+    match gradient {
+        Gradient::None => {}
+        Gradient::Linear { slope } => {
+            ui.horizontal(|ui| {
+                ui.label("slope");
+                ui.add(egui::DragValue::new(&mut slope.x).speed(0.0001).prefix("x: "));
+                ui.add(egui::DragValue::new(&mut slope.y).speed(0.0001).prefix("y: "));
+            });
+        }
+        Gradient::Radial { change, radius } => {
+            ui.add(egui::Slider::new(change, -1.0..=1.0).text("change"));
+            ui.add(egui::Slider::new(radius, 1.0..=500.0).text("at radius"));
+        }
+        // only code can change it
+        Gradient::Custom(_) => {
+            ui.label("set from code");
+        }
+    }
+}
+
+/// edits the fields of one shape. the rays are traced again every frame so changes show right away
+pub fn shape_properties(ui: &mut egui::Ui, shape: &mut Shape) {
+    transform_edit(ui, shape.transform_mut());
+    ui.separator();
+    match shape {
+        Shape::Line(line) => {
+            vec2_edit(ui, "start", &mut line.start);
+            vec2_edit(ui, "end", &mut line.end);
+            ui.add(egui::Slider::new(&mut line.thickness, 0.5..=10.0).text("thickness"));
+            material_edit(ui, &mut line.material, &SURFACES);
+            ui.checkbox(&mut line.one_sided, "one-sided (hatched on the back)");
+        }
+        Shape::Circle(circle) => {
+            vec2_edit(ui, "center", &mut circle.pos);
+            ui.add(egui::Slider::new(&mut circle.radius, 1.0..=500.0).text("radius"));
+            material_edit(ui, &mut circle.material, &SURFACES);
+        }
+        Shape::Medium(medium) => {
+            egui::ScrollArea::vertical().id_source("points").max_height(150.0).show(ui, |ui| {
+                medium.sides.resize(medium.points.len(), Side::Straight);
+                let ends: Vec<(Vec2, Vec2)> = medium.edges().map(|(start, end, _)| (start, end)).collect();
+                for (index, (point, side)) in medium.points.iter_mut().zip(medium.sides.iter_mut()).enumerate() {
+                    vec2_edit(ui, &format!("point {}", index), point);
+                    let (start, end) = ends[index];
+                    side_edit(ui, index, side, start, end);
+                }
+            });
+            ui.horizontal(|ui| {
+                // the new point goes halfway along the last side
+                if ui.button("add point").clicked() {
+                    let (start, end, _) = medium.edges().last().unwrap_or_default();
+                    // the last side now ends at the new point, so it can't keep its old curve
+                    medium.sides.truncate(medium.points.len().saturating_sub(1));
+                    medium.points.push((start + end) / 2.0);
+                }
+                let remove = ui.add_enabled(medium.points.len() > 3, egui::Button::new("remove point"));
+                if remove.clicked() {
+                    medium.points.pop();
+                    medium.sides.truncate(medium.points.len().saturating_sub(1));
+                }
+            });
+
+            let mut refractive_index = medium.refractive_index();
+            index_edit(ui, &mut refractive_index);
+            medium.set_refractive_index(refractive_index);
+            absorption_edit(ui, &mut medium.absorption);
+            gradient_edit(ui, &mut medium.gradient);
+
+            let mut color: [f32; 4] = medium.color().into();
+            ui.horizontal(|ui| {
+                ui.label("color");
+                if ui.color_edit_button_rgba_unmultiplied(&mut color).changed() {
+                    medium.set_color(color.into());
+                }
+            });
+            let mut options = vec![Material::Refractive];
+            options.extend(SURFACES);
+            material_edit(ui, &mut medium.material, &options);
+        }
+        Shape::Mirror(mirror) => {
+            curve_edit(ui, &mut mirror.curve);
+            ui.add(egui::Slider::new(&mut mirror.thickness, 0.5..=10.0).text("thickness"));
+            material_edit(ui, &mut mirror.material, &SURFACES);
+            ui.checkbox(&mut mirror.one_sided, "one-sided (hatched on the back)");
+        }
+        Shape::Detector(detector) => {
+            vec2_edit(ui, "start", &mut detector.start);
+            vec2_edit(ui, "end", &mut detector.end);
+            ui.add(egui::Slider::new(&mut detector.bins, 1..=128).text("bins"));
+            material_edit(ui, &mut detector.material, &SURFACES);
+        }
+    }
+}
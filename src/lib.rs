@@ -0,0 +1,24 @@
+//! 2d ray tracing with mirrors, refractive mediums and detectors.
+//!
+//! the tracer has no window dependency. drawing with nannou is behind the `draw` feature.
+
+pub mod bezier;
+pub mod camera;
+pub mod circles;
+pub mod color;
+pub mod detectors;
+pub mod editor;
+pub mod geometry;
+pub mod history;
+pub mod lights;
+pub mod lines;
+pub mod materials;
+pub mod mediums;
+pub mod mirrors;
+pub mod rays;
+pub mod scene;
+pub mod spectrum;
+pub mod transform;
+
+#[cfg(feature = "draw")]
+pub mod draw;
@@ -0,0 +1,97 @@
+use std::f32::consts::PI;
+
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::rays::Ray;
+use crate::spectrum::{self, Spectrum, REFERENCE_WAVELENGTH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LightKind {
+    /// rays in every direction
+    LightBulb,
+    /// one ray along `direction`
+    SingleRay,
+    /// a fan of rays around `direction`
+    FOV,
+}
+
+/// where the rays come from
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Light {
+    pub kind: LightKind,
+    pub position: Vec2,
+    /// where SingleRay and FOV are aimed
+    pub direction: Vec2,
+    pub ray_num: usize,
+    /// width of the FOV fan in degrees
+    pub fov: f32,
+    /// the power of each ray, shared between its wavelengths
+    pub power: f32,
+    pub spectrum: Spectrum,
+    /// in nanometers, for `Spectrum::Single`
+    pub wavelength: f32,
+    /// how many wavelengths `Spectrum::White` is split into
+    pub colors: usize,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Light {
+            kind: LightKind::FOV,
+            position: vec2(150.0, -150.0),
+            direction: vec2(1.0, 0.0),
+            ray_num: 50,
+            fov: 50.0,
+            power: 1.0,
+            spectrum: Spectrum::Plain,
+            wavelength: REFERENCE_WAVELENGTH,
+            colors: 7,
+        }
+    }
+}
+
+impl Light {
+    /// makes the rays of this light, they still need to be traced
+    pub fn rays(&self) -> Vec<Ray> {
+        let rays: Vec<Ray> = match self.kind {
+            LightKind::LightBulb => (0..self.ray_num)
+                .map(|i| {
+                    let angle = 2.0 * PI / self.ray_num as f32 * i as f32;
+                    let direction = vec2(angle.cos(), angle.sin());
+                    Ray::new(self.position, direction, direction)
+                })
+                .collect(),
+            LightKind::SingleRay => vec![Ray::new(self.position, self.direction, Vec2::ZERO)],
+            LightKind::FOV => {
+                let aim = self.direction.y.atan2(self.direction.x);
+                (0..self.ray_num)
+                    .map(|i| {
+                        let offset = ((i as f32 - self.ray_num as f32 / 2.0) / self.ray_num as f32) * self.fov.to_radians();
+                        let angle = aim + offset;
+                        Ray::new(self.position, vec2(angle.cos(), angle.sin()), vec2(offset.cos(), offset.sin()))
+                    })
+                    .collect()
+            }
+        };
+
+        // every direction gets a ray for each wavelength
+        let wavelengths = match self.spectrum {
+            Spectrum::Plain => vec![None],
+            Spectrum::Single => vec![Some(self.wavelength)],
+            Spectrum::White => spectrum::visible_wavelengths(self.colors).into_iter().map(Some).collect(),
+        };
+        let power = self.power / wavelengths.len() as f32;
+        rays.into_iter()
+            .flat_map(|ray| {
+                wavelengths.iter().map(move |&wavelength| {
+                    let mut ray = ray.clone();
+                    ray.power = power;
+                    ray.wavelength = wavelength;
+                    ray
+                })
+            })
+            .collect()
+    }
+}
@@ -1,12 +1,23 @@
-use nannou::prelude::*;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
 
-use crate::rays::Shape_Util;
+use crate::geometry::{distance_to_segment, ray_segment_intersection, side};
+use crate::materials::Material;
+use crate::rays::{Hit, Shape_Util, EPSILON};
+use crate::transform::Transform;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Line {
     pub start: Vec2,
     pub end: Vec2,
     pub thickness: f32,
+    #[serde(default)]
+    pub material: Material,
+    /// rays only hit it from the front, the left going from start to end, and go through it from the back
+    #[serde(default)]
+    pub one_sided: bool,
+    #[serde(default)]
+    pub transform: Transform,
 }
 
 impl Line {
@@ -15,6 +26,16 @@ impl Line {
             start,
             end,
             thickness,
+            material: Material::Mirror,
+            one_sided: false,
+            transform: Transform::default(),
+        }
+    }
+    /// a half silvered line that reflects `reflectivity` of each ray's power and lets the rest through
+    pub fn beam_splitter(start: Vec2, end: Vec2, reflectivity: f32) -> Self {
+        Line {
+            material: Material::PartialMirror { reflectivity },
+            ..Line::from(start, end, 1.0)
         }
     }
     pub fn new() -> Self {
@@ -22,61 +43,33 @@ impl Line {
             start: Vec2::ZERO,
             end: Vec2::ZERO,
             thickness: 1.0,
+            material: Material::Mirror,
+            one_sided: false,
+            transform: Transform::default(),
         }
     }
 
-    /// find the distance between this line and a point
+    /// how far a point in the world is from the closest point of the line
     pub fn distance_to_point(&self, point: &Vec2) -> f32 {
-        // Calculate the direction vector of the line
-        let line_direction = vec2(self.end.x - self.start.x, self.end.y - self.start.y);
-        let point_to_target = vec2(point.x - self.start.x, point.y - self.start.y);
-
-        // Calculate the dot product of the line direction and point_to_target
-        let dot_product =
-            line_direction.x * point_to_target.x + line_direction.y * point_to_target.y;
-
-        // Calculate the distance from the point to the line
-        let distance = dot_product.abs() / line_direction.length();
-
-        // Return the distance
-        distance
+        let line = self.to_world();
+        distance_to_segment(line.start, line.end, *point)
     }
-    pub fn slope(&self) -> Option<f32> {
-        let dx = self.end.x - self.start.x + 0.0001;
-        let dy = self.end.y - self.start.y + 0.0001;
-
-        // Check if the line is vertical (infinite slope)
-        if dx == 0.0 {
-            None
-        } else {
-            Some(dy / dx)
-        }
+    pub fn to_vector(&self) -> Vec2 {
+        self.end - self.start
     }
-
-    pub fn intercept(&self) -> Option<f32> {
-        // Calculate the slope (if not vertical)
-        if let Some(slope) = self.slope() {
-            let intercept = self.start.y - slope * self.start.x;
-            Some(intercept)
-        } else {
-            // Line is vertical, no intercept
-            None
-        }
+    /// unit normal on the left going from start to end, the front of the line. zero when the ends are the same
+    pub fn normal(&self) -> Vec2 {
+        self.to_vector().perp().normalize_or_zero()
     }
-    pub fn equation_to_line(m: f32, b: f32) -> Self {
-        let start_x = 1.0;
-        let start_y = m * start_x + b;
-        let end_x = 10.0;
-        let end_y = m * end_x + b;
+    /// the same line with its ends moved into the world
+    pub fn to_world(&self) -> Line {
         Line {
-            start: vec2(start_x, start_y),
-            end: vec2(end_x, end_y),
-            thickness: 1.0,
+            start: self.transform.to_world(self.start),
+            end: self.transform.to_world(self.end),
+            transform: Transform::default(),
+            ..*self
         }
     }
-    pub fn to_vector(&self) -> Vec2 {
-        self.end - self.start
-    }
 }
 
 impl Shape_Util for Line {
@@ -84,32 +77,25 @@ impl Shape_Util for Line {
     fn compare(&self, line: &Line) -> bool {
         return self.start == line.start && self.end == line.end;
     }
+    fn transform(&self) -> Transform {
+        self.transform
+    }
     /// point: the point you want to find intersection with
     ///
     /// thickness: the minimum distance an intersect will trigger
-    ///
-    /// written by chat GPT
-    fn intersect(&self, point: &Vec2) -> bool {
-        let v1 = vec2(self.end.x - self.start.x, self.end.y - self.start.y);
-        let v2 = vec2(point.x - self.start.x, point.y - self.start.y);
-
-        let dot_product = v1.x * v2.x + v1.y * v2.y;
-        let squared_length_v1 = v1.x * v1.x + v1.y * v1.y;
-
-        // Calculate the t value along the line segment
-        let t = dot_product / squared_length_v1;
-
-        // Check if the intersection point is within the bounds of the line segment
-        if t >= 0.0 && t <= 1.0 {
-            // Calculate the point of intersection on the line
-            let intersection_point = vec2(self.start.x + t * v1.x, self.start.y + t * v1.y);
-
-            // Calculate the distance between the intersection point and the input point
-            let distance = (intersection_point.x - point.x).hypot(intersection_point.y - point.y);
-
-            return distance <= self.thickness;
-        }
-
-        false
+    fn local_intersect(&self, point: &Vec2) -> bool {
+        distance_to_segment(self.start, self.end, *point) <= self.thickness
+    }
+    /// intersects the ray with the segment itself, ignores thickness. the normal faces the incoming ray,
+    /// so it's the front normal when hit from the front and the back one otherwise
+    fn local_ray_intersect(&self, origin: Vec2, direction: Vec2) -> Option<Hit> {
+        let distance = ray_segment_intersection(origin, direction, self.start, self.end).filter(|&distance| distance > EPSILON)?;
+        // the ray starts on the front when it's on the left of the line
+        let normal = if side(self.start, self.end, origin) >= 0.0 { self.normal() } else { -self.normal() };
+        Some(Hit {
+            distance,
+            point: origin + direction * distance,
+            normal,
+        })
     }
 }
@@ -4,32 +4,47 @@ use nannou::prelude::*;
 use nannou_egui::egui::InnerResponse;
 use nannou_egui::{self, egui, Egui};
 
-mod circles;
-mod lines;
-mod rays;
-mod mediums;
-use circles::Circle;
-use lines::Line;
-use rays::Ray;
-use rays::Shape;
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum State{
-    LightBulb,
-    SingleRay,
-    FOV,
-}
+mod inspector;
+
+use ray_tracing::camera::Camera;
+use ray_tracing::circles::Circle;
+use ray_tracing::color::Color;
+use ray_tracing::detectors::Detector;
+use ray_tracing::editor::{Editor, GRAB_DISTANCE};
+use ray_tracing::history::History;
+use ray_tracing::lights::LightKind;
+use ray_tracing::lines::Line;
+use ray_tracing::mediums;
+use ray_tracing::mirrors::Mirror;
+use ray_tracing::rays::Ray;
+use ray_tracing::rays::Shape;
+use ray_tracing::scene::Scene;
+use ray_tracing::spectrum::{Spectrum, VISIBLE_MAX, VISIBLE_MIN};
+
 struct Model {
     // window: Window,
     egui: Egui,
     rays: Vec<Ray>,
-    shapes: Vec<Shape>,
-    ray_num: usize,
-    state: State,
-    last_state: State,
-    fov: f32,
-    
+    scene: Scene,
+    /// where Save and Load read and write the scene
+    scene_path: String,
+    /// result of the last save or load
+    scene_status: String,
+    /// the mouse edits shapes instead of moving the light
+    editing: bool,
+    editor: Editor,
+    history: History,
+    camera: Camera,
+    /// where the mouse was on the window last frame, for panning
+    last_mouse: Vec2,
+    /// egui is using the mouse, so the scene shouldn't
+    ui_has_pointer: bool,
+    /// egui is using the keyboard, so the scene shouldn't
+    ui_has_keyboard: bool,
 }
+/// how much one step of the mouse wheel zooms
+const ZOOM_SPEED: f32 = 1.1;
+
 // TODO: https://www.youtube.com/watch?v=naaeH1qbjdQ
 fn main() {
     nannou::app(model).update(update).run();
@@ -40,35 +55,56 @@ fn model(app: &App) -> Model {
         .new_window()
         .view(view)
         .raw_event(raw_window_event)
+        .mouse_pressed(mouse_pressed)
+        .mouse_released(mouse_released)
+        .mouse_wheel(mouse_wheel)
+        .key_pressed(key_pressed)
         .build()
         .unwrap();
     let window = app.window(window_id).unwrap();
     let egui = Egui::from_window(&window);
-    let num = 0.0;
-    // let mut shapes = Vec::new();
-    let mut rays = vec![];
-    let ray_num = 50;
-    for i in 0..ray_num {
-        let angle = 2.0 * PI / ray_num as f32 * i as f32;
-        rays.push(Ray::new(
-            // vec2(0.0, 0.0),
-            vec2(150.0, -150.0),
-            vec2(angle.cos(), angle.sin()),
-            vec2(angle.cos(), angle.sin()),
-        ));
+
+    // a scene file can be passed as the first argument
+    let (scene, scene_path, scene_status) = match std::env::args().nth(1) {
+        Some(path) => match Scene::load(&path) {
+            Ok(scene) => (scene, path, String::new()),
+            Err(error) => {
+                eprintln!("{}", error);
+                (default_scene(), path, error.to_string())
+            }
+        },
+        None => (default_scene(), String::from("scene.ron"), String::new()),
+    };
+    let rays = scene.trace_light();
+    let history = History::new(&scene);
+
+    Model {
+        egui,
+        rays,
+        scene,
+        scene_path,
+        scene_status,
+        editing: false,
+        editor: Editor::default(),
+        history,
+        camera: Camera::default(),
+        last_mouse: Vec2::ZERO,
+        ui_has_pointer: false,
+        ui_has_keyboard: false,
     }
-    let shapes = vec![
+}
+
+fn default_scene() -> Scene {
+    Scene::new(vec![
         Shape::Line(Line::from(vec2(100.0, -100.0), vec2(150.0, 150.0), 1.0)),
         Shape::Line(Line::from(vec2(-150.0, 150.0), vec2(-100.0, -100.0), 1.0)),
         // Shape::Line(Line::from(vec2(-100.0, 100.0), vec2(100.0, 100.0), 1.0)),
         Shape::Line(Line::from(vec2(-100.0, -100.0), vec2(100.0, -110.0), 1.0)),
         Shape::Circle(Circle::from(vec2(100.0, 0.0), 50.0)),
-        Shape::Medium(mediums::Medium::new(vec2(-500.0, -100.0), vec2(500.0, 100.0), 1.5, rgba(0.0, 0.0, 1.0, 0.5))),
-        // Shape::Medium(mediums::Medium::new(vec2(-300.0, -500.0), vec2(-500.0, -100.0), 1.5, rgba(0.0, 0.0, 1.0, 0.5))),
-        // Shape::Medium(mediums::Medium::new(vec2(300.0, -500.0), vec2(500.0, -100.0), 1.5, rgba(0.0, 0.0, 1.0, 0.5))),
-    ];
-
-    Model { egui, rays, shapes, state: State::FOV, last_state: State::LightBulb, ray_num, fov: 50.0}
+        Shape::Medium(mediums::Medium::new(vec2(-500.0, -100.0), vec2(500.0, 100.0), mediums::GLASS, Color::new(0.0, 0.0, 1.0, 0.5))),
+        // Shape::Medium(mediums::Medium::new(vec2(-300.0, -500.0), vec2(-500.0, -100.0), mediums::WATER, Color::new(0.0, 0.0, 1.0, 0.5))),
+        // Shape::Medium(mediums::Medium::new(vec2(300.0, -500.0), vec2(500.0, -100.0), mediums::DIAMOND, Color::new(0.0, 0.0, 1.0, 0.5))),
+    ])
 }
 
 fn update(app: &App, model: &mut Model, update: Update) {
@@ -80,49 +116,252 @@ fn update(app: &App, model: &mut Model, update: Update) {
 
         egui::Window::new("Rum window").show(&ctx, |ui| {
             ui.label("controls");
-            let ray_num_number = ui.add(egui::Slider::new(&mut model.ray_num, 0..=100).text("Rays"));
-            if model.state == State::FOV {
-                let fov_change = ui.add(egui::Slider::new(&mut model.fov, 0.0..=180.0).text("FOV"));
-                if fov_change.changed() {
-                    // println!("changed fov");
-                    model.rays = update_ray_state(&model.rays, model.state, model.ray_num, model.fov, app);
-
+            ui.horizontal(|ui| {
+                if ui.add_enabled(model.history.can_undo(), egui::Button::new("Undo")).clicked() {
+                    model.history.undo(&mut model.scene);
+                }
+                if ui.add_enabled(model.history.can_redo(), egui::Button::new("Redo")).clicked() {
+                    model.history.redo(&mut model.scene);
                 }
+            });
+            let light = &mut model.scene.light;
+            ui.add(egui::Slider::new(&mut light.ray_num, 0..=100).text("Rays"));
+            ui.add(egui::Slider::new(&mut light.power, 0.0..=1.0).text("Ray power"));
+            if light.kind == LightKind::FOV {
+                ui.add(egui::Slider::new(&mut light.fov, 0.0..=180.0).text("FOV"));
             }
-            if ray_num_number.changed() {
-                model.rays = update_ray_state(&model.rays, model.state, model.ray_num, model.fov, app);
-
+            ui.add(egui::Slider::new(&mut model.scene.ambient_index, 1.0..=3.0).text("Ambient n"));
+            ui.add(egui::Slider::new(&mut model.scene.bounces, 0..=100).text("Bounces"));
+            ui.add(egui::Slider::new(&mut model.scene.max_length, 100.0..=10000.0).logarithmic(true).text("Ray length"));
+            ui.checkbox(&mut model.scene.fresnel, "Partial reflection");
+            ui.add(egui::Slider::new(&mut model.scene.min_power, 0.001..=0.5).logarithmic(true).text("Min power"));
+            ui.add(egui::Slider::new(&mut model.scene.grin_step, 0.5..=20.0).text("Gradient step"));
+            ui.add(egui::Slider::new(&mut model.scene.diffuse_rays, 1..=32).text("Diffuse rays"));
+            if ui.button("Fit scene").clicked() {
+                let (min, max) = model.scene.bounds();
+                let window = app.window_rect();
+                model.camera.fit(min, max, vec2(window.w(), window.h()));
             }
-            egui::ComboBox::from_label("Choose state").selected_text(format!("{:?}", model.state))
+            let stats = Scene::stats(&model.rays);
+            if stats.total() > 0.0 {
+                ui.label(format!(
+                    "absorbed {:.0}%, in mediums {:.0}%, escaped {:.0}%, untraced {:.0}%",
+                    100.0 * stats.absorbed / stats.total(),
+                    100.0 * stats.attenuated / stats.total(),
+                    100.0 * stats.escaped / stats.total(),
+                    100.0 * stats.untraced / stats.total(),
+                ));
+            }
+            // taken again, "Fit scene" borrows the whole scene for its bounds
+            let light = &mut model.scene.light;
+            egui::ComboBox::from_label("Choose state").selected_text(format!("{:?}", light.kind))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut light.kind, LightKind::LightBulb, "LightBulb");
+                ui.selectable_value(&mut light.kind, LightKind::SingleRay, "SingleRay");
+                ui.selectable_value(&mut light.kind, LightKind::FOV, "FOV");
+            });
+            egui::ComboBox::from_label("Light color").selected_text(format!("{:?}", light.spectrum))
             .show_ui(ui, |ui| {
-                ui.selectable_value(&mut model.state, State::LightBulb, "LightBulb");
-                ui.selectable_value(&mut model.state, State::SingleRay, "SingleRay");
-                ui.selectable_value(&mut model.state, State::FOV, "FOV");
+                ui.selectable_value(&mut light.spectrum, Spectrum::Plain, "Plain");
+                ui.selectable_value(&mut light.spectrum, Spectrum::Single, "Single");
+                ui.selectable_value(&mut light.spectrum, Spectrum::White, "White");
             });
+            match light.spectrum {
+                Spectrum::Plain => {}
+                Spectrum::Single => {
+                    ui.add(egui::Slider::new(&mut light.wavelength, VISIBLE_MIN..=VISIBLE_MAX).text("Wavelength (nm)"));
+                }
+                Spectrum::White => {
+                    ui.add(egui::Slider::new(&mut light.colors, 1..=30).text("Colors"));
+                }
+            }
+
+            ui.separator();
+            ui.label("scene file");
+            ui.text_edit_singleline(&mut model.scene_path);
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    model.scene_status = match model.scene.save(&model.scene_path) {
+                        Ok(()) => format!("saved {}", model.scene_path),
+                        Err(error) => error.to_string(),
+                    };
+                }
+                if ui.button("Load").clicked() {
+                    model.scene_status = match Scene::load(&model.scene_path) {
+                        Ok(scene) => {
+                            model.scene = scene;
+                            model.editor = Editor::default();
+                            format!("loaded {}", model.scene_path)
+                        }
+                        Err(error) => error.to_string(),
+                    };
+                }
+            });
+            if !model.scene_status.is_empty() {
+                ui.label(&model.scene_status);
+            }
+
+            ui.separator();
+            ui.checkbox(&mut model.editing, "Edit mode");
+            if model.editing {
+                ui.checkbox(&mut model.editor.snap, "Snap to other shapes");
+                // new shapes are built around their own origin and moved to the middle of the view
+                let mut added = None;
+                ui.horizontal(|ui| {
+                    if ui.button("Add line").clicked() {
+                        added = Some(Shape::Line(Line::from(vec2(-50.0, 0.0), vec2(50.0, 0.0), 1.0)));
+                    }
+                    if ui.button("Add beam splitter").clicked() {
+                        added = Some(Shape::Line(Line::beam_splitter(vec2(-35.0, -35.0), vec2(35.0, 35.0), 0.5)));
+                    }
+                    if ui.button("Add detector").clicked() {
+                        added = Some(Shape::Detector(Detector::new(vec2(0.0, -100.0), vec2(0.0, 100.0))));
+                    }
+                    if ui.button("Add circle").clicked() {
+                        added = Some(Shape::Circle(Circle::from(Vec2::ZERO, 30.0)));
+                    }
+                    if ui.button("Add medium").clicked() {
+                        let medium = mediums::Medium::new(vec2(-50.0, -50.0), vec2(50.0, 50.0), mediums::GLASS, Color::new(0.0, 0.0, 1.0, 0.5));
+                        added = Some(Shape::Medium(medium));
+                    }
+                    if ui.button("Add prism").clicked() {
+                        let points = vec![vec2(-50.0, -43.0), vec2(50.0, -43.0), vec2(0.0, 43.0)];
+                        let medium = mediums::Medium::polygon(points, mediums::FLINT_GLASS, Color::new(0.0, 0.0, 1.0, 0.5));
+                        added = Some(Shape::Medium(medium));
+                    }
+                    if ui.button("Add lens").clicked() {
+                        let medium = mediums::Medium::biconvex(Vec2::ZERO, 150.0, 20.0, 100.0, mediums::CROWN_GLASS, Color::new(0.0, 0.0, 1.0, 0.5));
+                        added = Some(Shape::Medium(medium));
+                    }
+                    if ui.button("Add droplet").clicked() {
+                        let medium = mediums::Medium::disk(Vec2::ZERO, 50.0, mediums::WATER, Color::new(0.0, 0.0, 1.0, 0.5));
+                        added = Some(Shape::Medium(medium));
+                    }
+                });
+                // curved mirrors are built around their center or vertex
+                ui.horizontal(|ui| {
+                    if ui.button("Add arc mirror").clicked() {
+                        added = Some(Shape::Mirror(Mirror::arc(Vec2::ZERO, 100.0, -PI / 4.0, PI / 4.0)));
+                    }
+                    if ui.button("Add parabolic mirror").clicked() {
+                        added = Some(Shape::Mirror(Mirror::parabola(Vec2::ZERO, 50.0, 200.0)));
+                    }
+                    if ui.button("Add elliptical mirror").clicked() {
+                        added = Some(Shape::Mirror(Mirror::ellipse(Vec2::ZERO, vec2(150.0, 100.0), -PI / 2.0, PI / 2.0)));
+                    }
+                    if ui.button("Add curve mirror").clicked() {
+                        let points = [vec2(-100.0, 0.0), vec2(-30.0, 100.0), vec2(30.0, -100.0), vec2(100.0, 0.0)];
+                        added = Some(Shape::Mirror(Mirror::bezier(points)));
+                    }
+                });
+                if let Some(mut shape) = added {
+                    shape.transform_mut().translation = model.camera.position;
+                    model.editor.add(&mut model.scene.shapes, shape);
+                }
+                let delete = ui.add_enabled(model.editor.selected.is_some(), egui::Button::new("Delete"));
+                if delete.clicked() {
+                    model.editor.delete_selected(&mut model.scene.shapes);
+                }
+            }
         });
+
+        egui::Window::new("Inspector").show(&ctx, |ui| {
+            inspector::shape_list(ui, &model.scene.shapes, &mut model.editor.selected);
+            if let Some(shape) = model.editor.selected.and_then(|selected| model.scene.shapes.get_mut(selected)) {
+                ui.separator();
+                inspector::shape_properties(ui, shape);
+            }
+        });
+
+        let detectors: Vec<(usize, &Detector)> = model
+            .scene
+            .shapes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, shape)| match shape {
+                Shape::Detector(detector) => Some((index, detector)),
+                _ => None,
+            })
+            .collect();
+        if !detectors.is_empty() {
+            egui::Window::new("Detectors").show(&ctx, |ui| {
+                for (index, detector) in detectors {
+                    ui.label(format!("{}: Detector", index));
+                    inspector::detector_plot(ui, index, detector, &Scene::detector_hits(&model.rays, index));
+                }
+            });
+        }
+
+        model.ui_has_pointer = ctx.wants_pointer_input() || ctx.is_pointer_over_area();
+        model.ui_has_keyboard = ctx.wants_keyboard_input();
     }
-    if model.state != model.last_state {
-        model.last_state = model.state;
-        model.rays = update_ray_state(&model.rays, model.state, model.ray_num, model.fov, app);
+
+    let mouse_on_window = app.mouse.position();
+    if app.mouse.buttons.middle().is_down() && !model.ui_has_pointer {
+        model.camera.pan(mouse_on_window - model.last_mouse);
     }
-    
+    model.last_mouse = mouse_on_window;
+    let mouse = model.camera.to_world(mouse_on_window);
+    // handles stay the same size on screen
+    model.editor.grab_distance = GRAB_DISTANCE / model.camera.zoom;
+
+    if model.editing {
+        if model.editor.is_dragging() {
+            model.editor.drag(&mut model.scene.shapes, mouse);
+        }
+    } else {
+        let light = &mut model.scene.light;
+        if app.mouse.buttons.left().is_down() && !model.ui_has_pointer {
+            light.position = mouse;
+        }
+        let aim = mouse - light.position;
+        if aim.length() > 0.0 {
+            light.direction = aim.normalize();
+        }
+    }
+    // a drag only becomes one undo step once the mouse is let go
+    model.history.update(&model.scene, app.mouse.buttons.left().is_down());
+    model.editor.selected = model.editor.selected.filter(|&selected| selected < model.scene.shapes.len());
+
+    model.rays = model.scene.trace_light();
+}
 
+fn mouse_pressed(app: &App, model: &mut Model, button: MouseButton) {
+    if model.editing && button == MouseButton::Left && !model.ui_has_pointer {
+        model.editor.press(&model.scene.shapes, model.camera.to_world(app.mouse.position()));
+    }
+}
 
+fn mouse_released(_app: &App, model: &mut Model, button: MouseButton) {
+    if button == MouseButton::Left {
+        model.editor.release();
+    }
+}
 
-    
+fn mouse_wheel(app: &App, model: &mut Model, delta: MouseScrollDelta, _phase: TouchPhase) {
+    if model.ui_has_pointer {
+        return;
+    }
+    let steps = match delta {
+        MouseScrollDelta::LineDelta(_, y) => y,
+        MouseScrollDelta::PixelDelta(position) => position.y as f32 / 50.0,
+    };
+    model.camera.zoom_at(app.mouse.position(), ZOOM_SPEED.powf(steps));
+}
 
-    for ray in model.rays.iter_mut() {
-        ray.ray_trace_loop(10, &model.shapes);
-        if model.state == State::SingleRay{
-            ray.start_direction = (app.mouse.position() - ray.start_position).normalize();
-        } else if model.state == State::FOV {
-            let angle = (app.mouse.position() - ray.start_position).angle() + ray.offset.angle();
-            ray.start_direction = vec2(angle.cos(), angle.sin());
-        }
-        if app.mouse.buttons.left().is_down() {
-            ray.start_position = app.mouse.position();
+fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    if model.ui_has_keyboard {
+        return;
+    }
+    if key == Key::Z && app.keys.mods.ctrl() {
+        if app.keys.mods.shift() {
+            model.history.redo(&mut model.scene);
+        } else {
+            model.history.undo(&mut model.scene);
         }
-        // ray.start_position = app.mouse.position();
+    }
+    if model.editing && (key == Key::Delete || key == Key::Back) {
+        model.editor.delete_selected(&mut model.scene.shapes);
     }
 }
 
@@ -134,73 +373,18 @@ fn view(app: &App, model: &Model, frame: Frame) {
     let draw = app.draw();
     draw.background().color(WHITE);
 
+    // everything in the scene is drawn through the camera
+    let world = draw
+        .scale(model.camera.zoom)
+        .translate(-model.camera.position.extend(0.0));
     for ray in &model.rays {
-        ray.show(&draw);
+        ray.show(&world);
     }
-    for shape in &model.shapes {
-        match shape {
-            Shape::Line(line) => {
-                draw.line()
-                    .start(line.start)
-                    .end(line.end)
-                    .weight(1.0)
-                    .color(BLACK);
-            }
-            Shape::Circle(circle) => {
-                draw.ellipse()
-                    .x_y(circle.pos.x, circle.pos.y)
-                    .radius(circle.radius)
-                    .color(BLACK);
-            }
-            Shape::Medium(medium) => {
-                medium.show(&draw);
-            }
-        }
+    model.scene.show(&world);
+    if model.editing {
+        model.editor.show(&world, &model.scene.shapes);
     }
 
     draw.to_frame(app, &frame).unwrap();
     model.egui.draw_to_frame(&frame).unwrap();
 }
-
-fn generate_fov(ray_num: usize, start_pos: Vec2, fov: f32) -> Vec<Ray> {
-    let mut rays = vec![];
-    for i in 0..ray_num {
-        let angle = ((i as f32 - ray_num as f32/2.0 ) / ray_num as f32) * fov;
-        rays.push(Ray::new(
-            start_pos,
-            vec2(angle.cos(), angle.sin()),
-            vec2(angle.cos(), angle.sin()),
-            
-        ));
-    }
-    rays
-
-}
-
-fn update_ray_state(rays_model: &Vec<Ray>, state_model: State, ray_num: usize, fov: f32, app: &App) -> Vec<Ray>{
-    let mut rays = vec![];
-    match state_model {
-        State::LightBulb => {
-            for i in 0..ray_num {
-                //
-                let angle = 2.0 * PI / ray_num as f32 * i as f32;
-                rays.push(Ray::new(
-                    // vec2(0.0, 0.0),
-                    vec2(150.0, -150.0),
-                    vec2(angle.cos(), angle.sin()),
-                    vec2(angle.cos(), angle.sin()),
-                ));
-            }
-            rays
-        }
-        State::SingleRay => {
-            let last_ray_pos = rays_model.first().unwrap().start_position;
-            vec![Ray::new(last_ray_pos, (app.mouse.position() - last_ray_pos).normalize(), Vec2::ZERO)]
-        }
-        State::FOV => {
-            let last_ray_pos = rays_model.first().unwrap().start_position;
-
-            generate_fov(ray_num, last_ray_pos, deg_to_rad(fov))
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// what a surface does to the rays that hit it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Material {
+    #[default]
+    Mirror,
+    /// the ray stops
+    Absorber,
+    /// mediums bend the ray with Snell's law. lines and circles have no inside, so rays pass through them
+    Refractive,
+    /// a matte surface that scatters the ray over every direction on the side it came from,
+    /// brightest straight out (Lambert's cosine law). the ray is split into a fan of `Scene::diffuse_rays`
+    Diffuse,
+    /// reflects `reflectivity` of the power, from 0 to 1, and lets the rest straight through
+    PartialMirror { reflectivity: f32 },
+    /// rays go straight through as if it wasn't there
+    Transparent,
+}
+
+impl Material {
+    pub fn refractive() -> Self {
+        Material::Refractive
+    }
+    pub fn absorber() -> Self {
+        Material::Absorber
+    }
+
+    /// if rays bounce off the surface, or part of them does
+    pub fn reflects(&self) -> bool {
+        matches!(self, Material::Mirror | Material::Diffuse | Material::PartialMirror { .. })
+    }
+}
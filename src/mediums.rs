@@ -1,44 +1,487 @@
-use nannou::prelude::*;
-use crate::rays::Shape_Util;
+use std::f32::consts::PI;
+use std::fmt;
 
-/// Remember to not switch between max and min
-#[derive(Clone, Copy, Debug)]
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+use crate::bezier::Bezier;
+use crate::color::Color;
+use crate::geometry::ray_segment_intersection;
+use crate::materials::Material;
+use crate::rays::{Hit, Shape_Util, EPSILON};
+use crate::spectrum::{BLUE, GREEN, RED};
+use crate::transform::Transform;
+
+pub const AIR: f32 = 1.0;
+pub const WATER: f32 = 1.33;
+pub const GLASS: f32 = 1.5;
+pub const DIAMOND: f32 = 2.42;
+/// BK7 crown glass, the usual lens glass
+pub const CROWN_GLASS: RefractiveIndex = RefractiveIndex::Sellmeier {
+    b: [1.039612, 0.23179234, 1.0104695],
+    c: [0.0060006987, 0.020017914, 103.56065],
+};
+/// SF11 flint glass, spreads colors a lot more than crown glass
+pub const FLINT_GLASS: RefractiveIndex = RefractiveIndex::Sellmeier {
+    b: [1.737597, 0.31374735, 1.898781],
+    c: [0.013188707, 0.062306814, 155.2363],
+};
+
+/// how the refractive index changes with the wavelength.
+///
+/// in a scene file a constant index is just the number, the others are written with their fields,
+/// like `(a: 1.5, b: 0.0042)`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RefractiveIndex {
+    /// the same for every wavelength
+    Constant(f32),
+    /// n = a + b / λ², λ in micrometers
+    Cauchy { a: f32, b: f32 },
+    /// n² = 1 + Σ bᵢλ² / (λ² - cᵢ), λ in micrometers
+    Sellmeier { b: [f32; 3], c: [f32; 3] },
+}
+
+impl RefractiveIndex {
+    /// the index for a wavelength in nanometers
+    pub fn at(&self, wavelength: f32) -> f32 {
+        let micrometers = wavelength / 1000.0;
+        let squared = micrometers * micrometers;
+        match *self {
+            RefractiveIndex::Constant(index) => index,
+            RefractiveIndex::Cauchy { a, b } => a + b / squared,
+            RefractiveIndex::Sellmeier { b, c } => {
+                let sum: f32 = (0..3).map(|i| b[i] * squared / (squared - c[i])).sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+}
+
+impl From<f32> for RefractiveIndex {
+    fn from(index: f32) -> Self {
+        RefractiveIndex::Constant(index)
+    }
+}
+
+/// how much light a medium soaks up per unit of length, so it keeps e^(-coefficient * length)
+/// of the power going through it.
+///
+/// like `RefractiveIndex`, a constant is written as just the number in a scene file
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Absorption {
+    /// the same for every wavelength
+    Constant(f32),
+    /// a coefficient for blue, green and red light, blended for the wavelengths in between
+    Colors { blue: f32, green: f32, red: f32 },
+}
+
+impl Absorption {
+    /// the coefficient for a wavelength in nanometers
+    pub fn at(&self, wavelength: f32) -> f32 {
+        match *self {
+            Absorption::Constant(coefficient) => coefficient,
+            Absorption::Colors { blue, green, red } => {
+                if wavelength < GREEN {
+                    let t = ((wavelength - BLUE) / (GREEN - BLUE)).clamp(0.0, 1.0);
+                    blue + (green - blue) * t
+                } else {
+                    let t = ((wavelength - GREEN) / (RED - GREEN)).clamp(0.0, 1.0);
+                    green + (red - green) * t
+                }
+            }
+        }
+    }
+}
+
+impl Default for Absorption {
+    fn default() -> Self {
+        Absorption::Constant(0.0)
+    }
+}
+
+/// how the refractive index changes over a medium, on top of its `RefractiveIndex`.
+/// positions are measured from the medium's center so the profile moves with it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Gradient {
+    /// the same index everywhere
+    #[default]
+    None,
+    /// the index goes up by `slope` per unit of length, along the slope
+    Linear { slope: Vec2 },
+    /// the index goes up by `change` at `radius` from the center, with the square of the distance.
+    /// a negative change makes a GRIN lens
+    Radial { change: f32, radius: f32 },
+    /// made in code. scenes with one can't be saved
+    #[serde(skip)]
+    Custom(IndexFunction),
+}
+
+/// gives how much is added to the index at a position relative to the medium's center
+#[derive(Clone, Copy)]
+pub struct IndexFunction(pub fn(Vec2) -> f32);
+
+impl PartialEq for IndexFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 as usize == other.0 as usize
+    }
+}
+
+impl fmt::Debug for IndexFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "IndexFunction")
+    }
+}
+
+impl Gradient {
+    /// how much is added to the index at a position relative to the center
+    pub fn at(&self, offset: Vec2) -> f32 {
+        match *self {
+            Gradient::None => 0.0,
+            Gradient::Linear { slope } => slope.dot(offset),
+            Gradient::Radial { change, radius } => change * offset.length_squared() / (radius * radius),
+            Gradient::Custom(IndexFunction(function)) => function(offset),
+        }
+    }
+}
+
+/// how far apart the index is sampled to find which way it changes
+const GRADIENT_DELTA: f32 = 0.5;
+
+/// how many straight pieces an arc or Bézier side is drawn with
+pub const ARC_STEPS: usize = 32;
+
+/// what a medium's side between two points looks like
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Side {
+    #[default]
+    Straight,
+    /// part of a circle, bulging out of the medium when the radius is positive and into it when it's negative.
+    /// a radius shorter than half the side is taken as half the side, which makes a half circle
+    Arc { radius: f32 },
+    /// a cubic Bézier curve pulled towards two control points, in the medium's coordinates
+    Bezier { controls: [Vec2; 2] },
+}
+
+impl Side {
+    /// the area between the side and the straight line under it, negative when it bulges in
+    fn bulge_area(&self, start: Vec2, end: Vec2) -> f32 {
+        let Side::Arc { radius } = *self else {
+            return 0.0;
+        };
+        let half = start.distance(end) / 2.0;
+        if half == 0.0 {
+            return 0.0;
+        }
+        let size = radius.abs().max(half);
+        let angle = 2.0 * (half / size).asin();
+        radius.signum() * size * size / 2.0 * (angle - angle.sin())
+    }
+}
+
+/// the circle an arc side lies on
+struct ArcSide {
+    center: Vec2,
+    /// negative when the arc bulges into the medium
+    radius: f32,
+    start: Vec2,
+    /// out of the medium, across the straight line from start to end
+    outward: Vec2,
+}
+
+impl ArcSide {
+    fn new(start: Vec2, end: Vec2, radius: f32, outward: Vec2) -> Self {
+        let half = start.distance(end) / 2.0;
+        let size = radius.abs().max(half);
+        let center = (start + end) / 2.0 - outward * radius.signum() * (size * size - half * half).sqrt();
+        ArcSide {
+            center,
+            radius: size * radius.signum(),
+            start,
+            outward,
+        }
+    }
+    /// if a point on the circle is on the arc and not on the rest of the circle
+    fn covers(&self, point: Vec2) -> bool {
+        (point - self.start).dot(self.outward) * self.radius >= 0.0
+    }
+    /// unit normal pointing out of the medium at a point on the arc
+    fn normal(&self, point: Vec2) -> Vec2 {
+        (point - self.center) / self.radius
+    }
+    /// if a point is between the arc and the straight line under it
+    fn bulge_contains(&self, point: Vec2) -> bool {
+        point.distance(self.center) <= self.radius.abs() && self.covers(point)
+    }
+    /// the point a fraction `t` of the way along the arc
+    fn point(&self, t: f32) -> Vec2 {
+        let angle = |point: Vec2| (point.y - self.center.y).atan2(point.x - self.center.x);
+        let first = angle(self.start);
+        // the middle of the arc tells which way around it goes
+        let mut half_turn = angle(self.center + self.outward * self.radius) - first;
+        if half_turn > PI {
+            half_turn -= 2.0 * PI;
+        } else if half_turn < -PI {
+            half_turn += 2.0 * PI;
+        }
+        let angle = first + 2.0 * half_turn * t;
+        self.center + vec2(angle.cos(), angle.sin()) * self.radius.abs()
+    }
+}
+
+/// a refractive area inside a closed outline of points, joined by straight or arc sides.
+/// the points can go around either way
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "MediumFile")]
 pub struct Medium {
-    pub min: Vec2,
-    pub max: Vec2,
-    refractive_index: f32,
-    color: Rgba,
+    /// in the medium's own coordinates, see `transform`
+    pub points: Vec<Vec2>,
+    /// the side from each point to the next one. sides that are missing are straight
+    pub sides: Vec<Side>,
+    refractive_index: RefractiveIndex,
+    color: Color,
+    pub material: Material,
+    pub absorption: Absorption,
+    pub gradient: Gradient,
+    pub transform: Transform,
+}
+
+/// how a medium is read from a scene file. a box can be written as `min` and `max` instead of `points`
+#[derive(Deserialize)]
+struct MediumFile {
+    #[serde(default)]
+    points: Vec<Vec2>,
+    #[serde(default)]
+    sides: Vec<Side>,
+    #[serde(default)]
+    min: Vec2,
+    #[serde(default)]
+    max: Vec2,
+    refractive_index: RefractiveIndex,
+    color: Color,
+    #[serde(default = "Material::refractive")]
+    material: Material,
+    #[serde(default)]
+    absorption: Absorption,
+    #[serde(default)]
+    gradient: Gradient,
+    #[serde(default)]
+    transform: Transform,
+}
+
+impl From<MediumFile> for Medium {
+    fn from(file: MediumFile) -> Self {
+        let points = if file.points.is_empty() {
+            Medium::box_points(file.min.min(file.max), file.min.max(file.max))
+        } else {
+            file.points
+        };
+        Medium {
+            points,
+            sides: file.sides,
+            refractive_index: file.refractive_index,
+            color: file.color,
+            material: file.material,
+            absorption: file.absorption,
+            gradient: file.gradient,
+            transform: file.transform,
+        }
+    }
 }
 
 impl Medium {
-    pub fn new(min: Vec2, max: Vec2, refractive_index: f32, color: Rgba) -> Self {
+    /// a box from its lower left to its upper right corner
+    pub fn new(min: Vec2, max: Vec2, refractive_index: impl Into<RefractiveIndex>, color: Color) -> Self {
+        Medium::polygon(Medium::box_points(min.min(max), min.max(max)), refractive_index, color)
+    }
+    pub fn polygon(points: Vec<Vec2>, refractive_index: impl Into<RefractiveIndex>, color: Color) -> Self {
         Medium {
-            min,
-            max,
-            refractive_index,
+            points,
+            sides: vec![],
+            refractive_index: refractive_index.into(),
             color,
+            material: Material::Refractive,
+            absorption: Absorption::default(),
+            gradient: Gradient::None,
+            transform: Transform::default(),
+        }
+    }
+    /// a round medium, like a water droplet
+    pub fn disk(center: Vec2, radius: f32, refractive_index: impl Into<RefractiveIndex>, color: Color) -> Self {
+        let mut disk = Medium::polygon(vec![center + vec2(0.0, radius), center - vec2(0.0, radius)], refractive_index, color);
+        disk.sides = vec![Side::Arc { radius }; 2];
+        disk
+    }
+    /// a lens with its axis along x.
+    ///
+    /// front_radius, back_radius: radii of curvature of the left and right surfaces, positive when the
+    /// center of curvature is to the right like in optics books. 0.0 makes a surface flat
+    ///
+    /// thickness: along the axis, aperture: how tall the lens is
+    pub fn lens(
+        center: Vec2,
+        front_radius: f32,
+        back_radius: f32,
+        thickness: f32,
+        aperture: f32,
+        refractive_index: impl Into<RefractiveIndex>,
+        color: Color,
+    ) -> Self {
+        let half = aperture / 2.0;
+        // how far the edge of a surface is to the right of where it crosses the axis
+        let sag = |radius: f32| {
+            if radius == 0.0 {
+                return 0.0;
+            }
+            let size = radius.abs().max(half);
+            radius.signum() * (size - (size * size - half * half).sqrt())
+        };
+        let side = |radius: f32| if radius == 0.0 { Side::Straight } else { Side::Arc { radius } };
+        let front = center.x - thickness / 2.0 + sag(front_radius);
+        let back = center.x + thickness / 2.0 + sag(back_radius);
+
+        // counterclockwise, so the front side goes down and bulges out to the left when its radius is positive
+        let points = vec![
+            vec2(front, center.y + half),
+            vec2(front, center.y - half),
+            vec2(back, center.y - half),
+            vec2(back, center.y + half),
+        ];
+        let mut lens = Medium::polygon(points, refractive_index, color);
+        lens.sides = vec![side(front_radius), Side::Straight, side(-back_radius), Side::Straight];
+        lens
+    }
+    /// a converging lens with the same curve on both sides
+    pub fn biconvex(center: Vec2, radius: f32, thickness: f32, aperture: f32, refractive_index: impl Into<RefractiveIndex>, color: Color) -> Self {
+        Medium::lens(center, radius, -radius, thickness, aperture, refractive_index, color)
+    }
+    /// a diverging lens with the same curve on both sides
+    pub fn biconcave(center: Vec2, radius: f32, thickness: f32, aperture: f32, refractive_index: impl Into<RefractiveIndex>, color: Color) -> Self {
+        Medium::lens(center, -radius, radius, thickness, aperture, refractive_index, color)
+    }
+    /// a lens curved on the left and flat on the right
+    pub fn plano_convex(center: Vec2, radius: f32, thickness: f32, aperture: f32, refractive_index: impl Into<RefractiveIndex>, color: Color) -> Self {
+        Medium::lens(center, radius, 0.0, thickness, aperture, refractive_index, color)
+    }
+    fn box_points(min: Vec2, max: Vec2) -> Vec<Vec2> {
+        vec![min, vec2(max.x, min.y), max, vec2(min.x, max.y)]
+    }
+    /// the side starting at a point
+    pub fn side(&self, index: usize) -> Side {
+        self.sides.get(index).copied().unwrap_or_default()
+    }
+    /// the sides as start and end points, the last one goes back to the first point
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2, Side)> + '_ {
+        self.points
+            .iter()
+            .copied()
+            .zip(self.points.iter().copied().cycle().skip(1))
+            .enumerate()
+            .map(|(index, (start, end))| (start, end, self.side(index)))
+    }
+    /// positive when the points go counterclockwise
+    pub fn signed_area(&self) -> f32 {
+        let chords = self.edges().map(|(start, end, _)| start.perp_dot(end)).sum::<f32>() / 2.0;
+        // a Bézier side is fixed by its control points, so the area it adds already has the right sign
+        let curves: f32 = self
+            .edges()
+            .filter_map(|(start, end, side)| Medium::bezier(start, end, side).map(|curve| (start, end, curve)))
+            .map(|(start, end, curve)| {
+                let points: Vec<Vec2> = (0..=ARC_STEPS).map(|step| curve.point(step as f32 / ARC_STEPS as f32)).collect();
+                (points.windows(2).map(|piece| piece[0].perp_dot(piece[1])).sum::<f32>() - start.perp_dot(end)) / 2.0
+            })
+            .sum();
+        let straight = chords + curves;
+        let bulges: f32 = self.edges().map(|(start, end, side)| side.bulge_area(start, end)).sum();
+        // the straight sides say which way the points go. with only two points they have no area,
+        // then the arcs do. arcs bulging out make the area bigger whichever way the points go
+        let counterclockwise = if straight == 0.0 { bulges >= 0.0 } else { straight > 0.0 };
+        if counterclockwise {
+            straight + bulges
+        } else {
+            straight - bulges
+        }
+    }
+    /// unit normal pointing out of the medium, across the straight line from start to end.
+    /// in the medium's coordinates
+    pub fn outward_normal(&self, start: Vec2, end: Vec2) -> Vec2 {
+        outward(start, end, self.signed_area() > 0.0)
+    }
+    /// the arc a side is on, if it's curved
+    fn arc(start: Vec2, end: Vec2, side: Side, counterclockwise: bool) -> Option<ArcSide> {
+        match side {
+            Side::Arc { radius } => Some(ArcSide::new(start, end, radius, outward(start, end, counterclockwise))),
+            _ => None,
+        }
+    }
+    /// the curve of a Bézier side
+    fn bezier(start: Vec2, end: Vec2, side: Side) -> Option<Bezier> {
+        match side {
+            Side::Bezier { controls: [first, second] } => Some(Bezier([start, first, second, end])),
+            _ => None,
+        }
+    }
+    /// points going around the medium in the world, with arcs and Bézier sides split into `ARC_STEPS` straight pieces
+    pub fn outline(&self) -> Vec<Vec2> {
+        let counterclockwise = self.signed_area() > 0.0;
+        let mut outline = vec![];
+        for (start, end, side) in self.edges() {
+            outline.push(start);
+            if let Some(arc) = Medium::arc(start, end, side, counterclockwise) {
+                outline.extend((1..ARC_STEPS).map(|step| arc.point(step as f32 / ARC_STEPS as f32)));
+            }
+            if let Some(curve) = Medium::bezier(start, end, side) {
+                outline.extend((1..ARC_STEPS).map(|step| curve.point(step as f32 / ARC_STEPS as f32)));
+            }
         }
+        outline.into_iter().map(|point| self.transform.to_world(point)).collect()
     }
-    pub fn show(&self, draw: &Draw) {
-        draw.rect()
-            .x_y((self.min.x + self.max.x) / 2.0, (self.min.y + self.max.y) / 2.0)
-            .w_h(self.max.x - self.min.x, self.max.y - self.min.y)
-            .color(self.color);
+    /// the corners of a box around the medium
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        self.outline()
+            .iter()
+            .fold((Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)), |(min, max), point| (min.min(*point), max.max(*point)))
     }
-    pub fn refractive_index(&self) -> f32 {
+    pub fn refractive_index(&self) -> RefractiveIndex {
         self.refractive_index
     }
-    pub fn color(&self) -> Rgba {
+    pub fn set_refractive_index(&mut self, refractive_index: impl Into<RefractiveIndex>) {
+        self.refractive_index = refractive_index.into();
+    }
+    /// the refractive index for a wavelength in nanometers at a point in the world.
+    /// the gradient turns and scales with the medium
+    pub fn index_at(&self, wavelength: f32, point: Vec2) -> f32 {
+        self.refractive_index.at(wavelength) + self.gradient.at(self.transform.to_local(point) - self.center())
+    }
+    /// the average of the points, in the medium's coordinates
+    pub fn center(&self) -> Vec2 {
+        self.points.iter().fold(Vec2::ZERO, |sum, point| sum + *point) / self.points.len().max(1) as f32
+    }
+    /// if rays curve inside this medium
+    pub fn is_graded(&self) -> bool {
+        self.gradient != Gradient::None
+    }
+    /// how fast a ray going along `direction` turns at a point, from the ray equation
+    /// d/ds(n dr/ds) = ∇n. only the part of ∇n across the ray bends it
+    pub fn curvature(&self, wavelength: f32, point: Vec2, direction: Vec2) -> Vec2 {
+        let index = |offset: Vec2| self.index_at(wavelength, point + offset);
+        let gradient = vec2(
+            index(vec2(GRADIENT_DELTA, 0.0)) - index(vec2(-GRADIENT_DELTA, 0.0)),
+            index(vec2(0.0, GRADIENT_DELTA)) - index(vec2(0.0, -GRADIENT_DELTA)),
+        ) / (2.0 * GRADIENT_DELTA);
+        (gradient - gradient.dot(direction) * direction) / index(Vec2::ZERO)
+    }
+    /// how much of the power is left after going `distance` through the medium
+    pub fn transmittance(&self, wavelength: f32, distance: f32) -> f32 {
+        (-self.absorption.at(wavelength) * distance).exp()
+    }
+    pub fn color(&self) -> Color {
         self.color
     }
-
-    pub fn calculate_refractive_angle(&self, n1: f32, enter_angle: f32,) -> Vec2{
-        let refractive_angle = (n1 * (enter_angle).sin() / self.refractive_index).asin();
-        if refractive_angle.is_nan() {
-            return vec2(refractive_angle.cos(), refractive_angle.sin());
-        }
-        vec2(refractive_angle.cos(), refractive_angle.sin())
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
     }
+
     pub fn calculate_refractive_angle_two_mediums(
     n1: f32,
     n2: f32,
@@ -65,48 +508,110 @@ impl Medium {
         Some(refracted_parallel + refracted_perpendicular)
 
     }
-    pub fn normal_at_point(&self, point: Vec2) -> Vec2 {
-        let distance_from_top = self.max.y - point.y;
-        let distance_from_bottom = point.y - self.min.y;
-        let distance_from_right = self.max.x - point.x;
-        let distance_from_left = point.x - self.min.x;
-
-        let mut largest_distance = distance_from_top;
-        let mut normal = vec2(0.0, -1.0);
-
-        if distance_from_bottom < largest_distance {
-            largest_distance = distance_from_bottom;
-            // println!("distance_from_bottom: {}", distance_from_bottom);
-            normal = vec2(0.0, 1.0);
-        }
-
-        if distance_from_right < largest_distance {
-            largest_distance = distance_from_right;
-            // println!("distance_from_right: {}", distance_from_right);
-
-            normal = vec2(-1.0, 0.0);
+    /// how much of the light is reflected at a border, for unpolarized light.
+    /// the rest goes through. 1.0 on total internal reflection
+    ///
+    /// normal: faces the incoming ray, like in `calculate_refractive_angle_two_mediums`
+    pub fn fresnel_reflectance(n1: f32, n2: f32, incident: Vec2, normal: Vec2) -> f32 {
+        let cos_i = -incident.normalize().dot(normal.normalize());
+        let sin_t2 = (n1 / n2) * (n1 / n2) * (1.0 - cos_i * cos_i);
+        if sin_t2 > 1.0 {
+            return 1.0;
         }
+        let cos_t = (1.0 - sin_t2).sqrt();
 
-        if distance_from_left < largest_distance {
-            // println!("distance_from_left: {}", distance_from_left);
-
-            normal = vec2(1.0, 0.0);
-        }
+        let perpendicular = (n1 * cos_i - n2 * cos_t) / (n1 * cos_i + n2 * cos_t);
+        let parallel = (n1 * cos_t - n2 * cos_i) / (n1 * cos_t + n2 * cos_i);
+        (perpendicular * perpendicular + parallel * parallel) / 2.0
+    }
+}
 
+/// unit normal of the straight line from start to end, on the outside of the medium
+fn outward(start: Vec2, end: Vec2, counterclockwise: bool) -> Vec2 {
+    // perp is on the left, which is inside when going counterclockwise
+    let normal = (end - start).perp().normalize_or_zero();
+    if counterclockwise {
+        -normal
+    } else {
         normal
-
-
     }
 }
 
 impl Shape_Util for Medium {
-    fn intersect(&self, point: &Vec2) -> bool {
-            point.x >= self.min.x
-            && point.x <= self.max.x
-            && point.y >= self.min.y
-            && point.y <= self.max.y
+    /// even-odd rule, so it works for any outline that doesn't cross itself
+    fn local_intersect(&self, point: &Vec2) -> bool {
+        let counterclockwise = self.signed_area() > 0.0;
+        let mut inside = false;
+        for (start, end, side) in self.edges() {
+            // count where a ray going right crosses the curve instead of its straight side
+            if let Some(curve) = Medium::bezier(start, end, side) {
+                let crossings = curve.ray_hits(*point, vec2(1.0, 0.0)).into_iter().filter(|&(distance, _)| distance > 0.0).count();
+                if crossings % 2 == 1 {
+                    inside = !inside;
+                }
+                continue;
+            }
+            if (start.y > point.y) != (end.y > point.y) {
+                let x = start.x + (point.y - start.y) / (end.y - start.y) * (end.x - start.x);
+                if point.x < x {
+                    inside = !inside;
+                }
+            }
+            // an arc bulging out adds the bit under it and one bulging in takes it away
+            if Medium::arc(start, end, side, counterclockwise).is_some_and(|arc| arc.bulge_contains(*point)) {
+                inside = !inside;
+            }
+        }
+        inside
     }
     fn compare(&self, medium: &Medium) -> bool {
-        self.min == medium.min && self.max == medium.max
+        self.points == medium.points && self.sides == medium.sides
+    }
+    fn transform(&self) -> Transform {
+        self.transform
+    }
+    /// the closest side in front of the ray. works from inside the medium too, then the hit is where the ray leaves
+    fn local_ray_intersect(&self, origin: Vec2, direction: Vec2) -> Option<Hit> {
+        let counterclockwise = self.signed_area() > 0.0;
+        let mut closest: Option<Hit> = None;
+        let mut keep = |distance: f32, normal: Vec2| {
+            if distance > EPSILON && closest.is_none_or(|hit| distance < hit.distance) {
+                closest = Some(Hit {
+                    distance,
+                    point: origin + direction * distance,
+                    normal,
+                });
+            }
+        };
+        for (start, end, side) in self.edges() {
+            if let Some(arc) = Medium::arc(start, end, side, counterclockwise) {
+                let center_to_origin = origin - arc.center;
+                let b = center_to_origin.dot(direction);
+                let c = center_to_origin.length_squared() - arc.radius * arc.radius;
+                let discriminant = b * b - c;
+                if discriminant < 0.0 {
+                    continue;
+                }
+                let root = discriminant.sqrt();
+                for distance in [-b - root, -b + root] {
+                    let point = origin + direction * distance;
+                    if arc.covers(point) {
+                        keep(distance, arc.normal(point));
+                    }
+                }
+                continue;
+            }
+            if let Some(curve) = Medium::bezier(start, end, side) {
+                for (distance, t) in curve.ray_hits(origin, direction) {
+                    keep(distance, outward(Vec2::ZERO, curve.tangent(t), counterclockwise));
+                }
+                continue;
+            }
+
+            if let Some(distance) = ray_segment_intersection(origin, direction, start, end) {
+                keep(distance, outward(start, end, counterclockwise));
+            }
+        }
+        closest
     }
-}
\ No newline at end of file
+}
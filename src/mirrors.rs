@@ -0,0 +1,240 @@
+use std::f32::consts::TAU;
+
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::bezier::Bezier;
+use crate::geometry::distance_to_segment;
+use crate::materials::Material;
+use crate::rays::{Hit, Shape_Util, EPSILON};
+use crate::transform::Transform;
+
+/// how many straight pieces a curved mirror is drawn and picked with
+pub const CURVE_STEPS: usize = 64;
+
+/// the shape of a curved mirror in its own coordinates. angles are in radians and go counterclockwise
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MirrorCurve {
+    /// part of a circle around the origin, from the `start` angle to the `end` angle
+    Arc { radius: f32, start: f32, end: f32 },
+    /// x = y² / (4 * focal_length), with the vertex at the origin and the focus at (focal_length, 0).
+    /// aperture: how tall it is
+    Parabola { focal_length: f32, aperture: f32 },
+    /// part of an ellipse around the origin with its radii along x and y. the angles are the
+    /// parameter t of (radii.x * cos t, radii.y * sin t)
+    Ellipse { radii: Vec2, start: f32, end: f32 },
+    /// a cubic Bézier curve from the first point to the last, pulled towards the two in the middle
+    Bezier { points: [Vec2; 4] },
+}
+
+/// how far the angle goes counterclockwise from start to end, a full turn at most
+fn sweep(start: f32, end: f32) -> f32 {
+    if (end - start).abs() >= TAU {
+        TAU
+    } else {
+        (end - start).rem_euclid(TAU)
+    }
+}
+
+fn sweeps(start: f32, end: f32, angle: f32) -> bool {
+    (angle - start).rem_euclid(TAU) <= sweep(start, end)
+}
+
+/// the distances along a ray where it meets a curve given by a quadratic a t² + b t + c = 0, nearest first
+fn roots(a: f32, b: f32, c: f32) -> Vec<f32> {
+    if a.abs() < f32::EPSILON {
+        if b.abs() < f32::EPSILON {
+            return vec![];
+        }
+        return vec![-c / b];
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+    let root = discriminant.sqrt();
+    let (first, second) = ((-b - root) / (2.0 * a), (-b + root) / (2.0 * a));
+    vec![first.min(second), first.max(second)]
+}
+
+/// unit normal of a Bézier curve at `t` along it, from its tangent
+fn bezier_normal(points: [Vec2; 4], t: f32) -> Vec2 {
+    Bezier(points).tangent(t).perp().normalize()
+}
+
+/// an open reflector bent along a curve, for focusing mirrors and whispering galleries
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mirror {
+    pub curve: MirrorCurve,
+    pub thickness: f32,
+    #[serde(default)]
+    pub material: Material,
+    /// rays only hit it from the front and go through it from the back
+    #[serde(default)]
+    pub one_sided: bool,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+impl Mirror {
+    pub fn new(curve: MirrorCurve, position: Vec2) -> Self {
+        Mirror {
+            curve,
+            thickness: 1.0,
+            material: Material::Mirror,
+            one_sided: false,
+            transform: Transform {
+                translation: position,
+                ..Transform::default()
+            },
+        }
+    }
+    /// part of a circle around `center`
+    pub fn arc(center: Vec2, radius: f32, start: f32, end: f32) -> Self {
+        Mirror::new(MirrorCurve::Arc { radius, start, end }, center)
+    }
+    /// a parabola opening to the right, turn it with the transform to point it somewhere else
+    pub fn parabola(vertex: Vec2, focal_length: f32, aperture: f32) -> Self {
+        Mirror::new(MirrorCurve::Parabola { focal_length, aperture }, vertex)
+    }
+    /// part of an ellipse around `center`
+    pub fn ellipse(center: Vec2, radii: Vec2, start: f32, end: f32) -> Self {
+        Mirror::new(MirrorCurve::Ellipse { radii, start, end }, center)
+    }
+    /// a freeform curve through the first and last point. points: in the mirror's own coordinates, before its transform
+    pub fn bezier(points: [Vec2; 4]) -> Self {
+        Mirror::new(MirrorCurve::Bezier { points }, Vec2::ZERO)
+    }
+
+    /// the point a fraction `t` of the way along the curve, in the mirror's coordinates
+    pub fn local_point(&self, t: f32) -> Vec2 {
+        match self.curve {
+            MirrorCurve::Arc { radius, start, end } => {
+                let angle = start + sweep(start, end) * t;
+                vec2(angle.cos(), angle.sin()) * radius
+            }
+            MirrorCurve::Parabola { focal_length, aperture } => {
+                let y = aperture * (t - 0.5);
+                vec2(y * y / (4.0 * focal_length), y)
+            }
+            MirrorCurve::Ellipse { radii, start, end } => {
+                let angle = start + sweep(start, end) * t;
+                vec2(angle.cos(), angle.sin()) * radii
+            }
+            MirrorCurve::Bezier { points } => Bezier(points).point(t),
+        }
+    }
+    /// points along the curve in the world, `CURVE_STEPS` pieces
+    pub fn outline(&self) -> Vec<Vec2> {
+        (0..=CURVE_STEPS)
+            .map(|step| self.transform.to_world(self.local_point(step as f32 / CURVE_STEPS as f32)))
+            .collect()
+    }
+    /// the corners of a box around the mirror
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        self.outline()
+            .iter()
+            .fold((Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)), |(min, max), point| (min.min(*point), max.max(*point)))
+    }
+    /// where the curve focuses light, in the world. the center of an arc, the focus of a parabola
+    /// and both foci of an ellipse. a Bézier curve has none
+    pub fn foci(&self) -> Vec<Vec2> {
+        let foci = match self.curve {
+            MirrorCurve::Arc { .. } => vec![Vec2::ZERO],
+            MirrorCurve::Parabola { focal_length, .. } => vec![vec2(focal_length, 0.0)],
+            MirrorCurve::Ellipse { radii, .. } => {
+                // on the longer axis
+                let offset = (radii.x * radii.x - radii.y * radii.y).abs().sqrt();
+                if radii.x >= radii.y {
+                    vec![vec2(-offset, 0.0), vec2(offset, 0.0)]
+                } else {
+                    vec![vec2(0.0, -offset), vec2(0.0, offset)]
+                }
+            }
+            MirrorCurve::Bezier { .. } => vec![],
+        };
+        foci.into_iter().map(|focus| self.transform.to_world(focus)).collect()
+    }
+    /// unit normal of the curve at a point on it, in the mirror's coordinates. not facing any side in particular
+    fn local_normal(&self, point: Vec2) -> Vec2 {
+        match self.curve {
+            MirrorCurve::Arc { .. } => point.normalize(),
+            MirrorCurve::Parabola { focal_length, .. } => vec2(-2.0 * focal_length, point.y).normalize(),
+            MirrorCurve::Ellipse { radii, .. } => (point / (radii * radii)).normalize(),
+            // at the closest point of the curve
+            MirrorCurve::Bezier { points } => bezier_normal(points, Bezier(points).closest(point)),
+        }
+    }
+    /// unit normal of the curve at a point on it in the world
+    pub fn normal(&self, point: Vec2) -> Vec2 {
+        self.transform.direction_to_world(self.local_normal(self.transform.to_local(point)))
+    }
+    /// which way the front of the mirror faces at a point on it in the world. the concave side of arcs,
+    /// parabolas and ellipses, where their foci are, and the left of a Bézier curve going from its first point
+    pub fn front(&self, point: Vec2) -> Vec2 {
+        match self.curve {
+            MirrorCurve::Bezier { .. } => self.normal(point),
+            _ => -self.normal(point),
+        }
+    }
+    /// if a point on the full curve is on the part the mirror covers
+    fn covers(&self, point: Vec2) -> bool {
+        match self.curve {
+            MirrorCurve::Arc { start, end, .. } => sweeps(start, end, point.y.atan2(point.x)),
+            MirrorCurve::Parabola { aperture, .. } => point.y.abs() <= aperture / 2.0,
+            MirrorCurve::Ellipse { radii, start, end } => sweeps(start, end, (point.y / radii.y).atan2(point.x / radii.x)),
+            MirrorCurve::Bezier { .. } => true,
+        }
+    }
+}
+
+impl Shape_Util for Mirror {
+    fn compare(&self, mirror: &Mirror) -> bool {
+        self.curve == mirror.curve
+    }
+    fn transform(&self) -> Transform {
+        self.transform
+    }
+    /// within `thickness` of the curve
+    fn local_intersect(&self, point: &Vec2) -> bool {
+        let points: Vec<Vec2> = (0..=CURVE_STEPS).map(|step| self.local_point(step as f32 / CURVE_STEPS as f32)).collect();
+        points.windows(2).any(|piece| distance_to_segment(piece[0], piece[1], *point) <= self.thickness)
+    }
+    /// solves for the curve exactly, the normal faces the incoming ray
+    fn local_ray_intersect(&self, origin: Vec2, direction: Vec2) -> Option<Hit> {
+        // the distances along the ray, with how far along the curve it is for Bézier curves
+        let untagged = |distances: Vec<f32>| distances.into_iter().map(|distance| (distance, None)).collect();
+        let crossings: Vec<(f32, Option<f32>)> = match self.curve {
+            MirrorCurve::Arc { radius, .. } => {
+                untagged(roots(direction.length_squared(), 2.0 * origin.dot(direction), origin.length_squared() - radius * radius))
+            }
+            MirrorCurve::Parabola { focal_length, .. } => untagged(roots(
+                direction.y * direction.y,
+                2.0 * origin.y * direction.y - 4.0 * focal_length * direction.x,
+                origin.y * origin.y - 4.0 * focal_length * origin.x,
+            )),
+            MirrorCurve::Ellipse { radii, .. } => {
+                // squashed into a unit circle
+                let (origin, direction) = (origin / radii, direction / radii);
+                untagged(roots(direction.length_squared(), 2.0 * origin.dot(direction), origin.length_squared() - 1.0))
+            }
+            MirrorCurve::Bezier { points } => Bezier(points).ray_hits(origin, direction).into_iter().map(|(distance, t)| (distance, Some(t))).collect(),
+        };
+        let (distance, point, t) = crossings
+            .into_iter()
+            .filter(|&(distance, _)| distance > EPSILON)
+            .map(|(distance, t)| (distance, origin + direction * distance, t))
+            .find(|&(_, point, _)| self.covers(point))?;
+
+        // Bézier curves already know where they were crossed, so it isn't looked for again
+        let normal = match (self.curve, t) {
+            (MirrorCurve::Bezier { points }, Some(t)) => bezier_normal(points, t),
+            _ => self.local_normal(point),
+        };
+        Some(Hit {
+            distance,
+            point,
+            normal: if normal.dot(direction) > 0.0 { -normal } else { normal },
+        })
+    }
+}
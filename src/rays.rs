@@ -1,202 +1,421 @@
 use crate::circles::Circle;
+use crate::detectors::{Detector, DetectorHit};
 use crate::lines::Line;
+use crate::materials::Material;
 use crate::mediums::Medium;
-use nannou::prelude::*;
+use crate::mirrors::Mirror;
+use crate::scene::{Scene, MAX_GRIN_STEPS};
+use crate::spectrum::REFERENCE_WAVELENGTH;
+use crate::transform::Transform;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
 
 #[derive(Clone, Debug)]
 pub struct Ray {
     pub start_position: Vec2,
     pub start_direction: Vec2,
     pub offset: Vec2,
+    /// the power the ray starts with
+    pub power: f32,
+    /// in nanometers. plain rays without one are traced at `REFERENCE_WAVELENGTH`
+    pub wavelength: Option<f32>,
+    segments: Vec<Segment>,
+    stats: PowerStats,
+    detector_hits: Vec<DetectorHit>,
+}
+
+/// a straight piece of a traced ray
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment {
+    pub start: Vec2,
+    pub end: Vec2,
+    /// the power at the start of the segment
+    pub power: f32,
+    /// the power left at the end, less than `power` inside absorbing mediums
+    pub end_power: f32,
+}
+
+/// where the power of a ray ended up after it was traced. adds up to the power it started with
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PowerStats {
+    /// taken by absorbers
+    pub absorbed: f32,
+    /// soaked up on the way through mediums
+    pub attenuated: f32,
+    /// left the scene
+    pub escaped: f32,
+    /// still going when the bounce limit or the min power stopped the trace, or when it ran out of
+    /// length inside a graded medium
+    pub untraced: f32,
+}
+
+/// a part of the ray that still has to be traced
+struct Branch {
     origin: Vec2,
-    pub direction: Vec2,      // normal
-    tracer: Vec2,            // the object that moves to trace
-    intersect: Option<Vec2>, // the end where the object is close
-    points_draw: Vec<Vec2>,
+    direction: Vec2,
+    power: f32,
+    /// stack of the mediums the branch is inside of, as indices into the scene's shapes
+    entered: Vec<usize>,
+    /// how many bounces came before this branch
+    depth: u32,
+    /// how far it curved through graded mediums since the last bounce
+    travelled: f32,
 }
 
+/// hits closer than this to the ray origin are ignored so a ray doesn't hit the surface it just left
+pub const EPSILON: f32 = 0.001;
+/// default for how far a ray looks for something to hit
+pub const MAX_DISTANCE: f32 = 1000.0;
 
+/// where a ray hits a shape
 #[derive(Clone, Copy, Debug)]
+pub struct Hit {
+    /// distance along the ray from its origin
+    pub distance: f32,
+    pub point: Vec2,
+    /// unit normal of the surface at `point`. points out of circles and mediums
+    pub normal: Vec2,
+}
+
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Shape {
     Circle(Circle),
     Line(Line),
     Medium(Medium),
+    Mirror(Mirror),
+    Detector(Detector),
 }
 
 impl Ray {
     pub fn new(origin: Vec2, direction: Vec2, offset: Vec2) -> Self {
         Ray {
-            origin,
             start_position: origin,
-            direction,
-            intersect: None,
-            tracer: origin,
-            points_draw: vec![],
             start_direction: direction,
             offset,
+            power: 1.0,
+            wavelength: None,
+            segments: vec![],
+            stats: PowerStats::default(),
+            detector_hits: vec![],
         }
     }
 
-    pub fn show(&self, draw: &Draw) {
-        for i in 0..self.points_draw.len() - 1 {
-            draw.line()
-                .start(self.points_draw[i])
-                .end(self.points_draw[i + 1])
-                .weight(1.0)
-                .color(BLACK);
-        }
+    /// the segments from the last trace. a ray splits where it partly reflects, so they form a tree:
+    /// each branch is traced to its end before the ones it split off from earlier
+    pub fn segments(&self) -> &Vec<Segment> {
+        &self.segments
     }
 
-    pub fn touching_object(&mut self, shapes: &Vec<Shape>) -> (Option<Shape>, Option<Vec2>) {
-        for shape in shapes {
-            match shape {
-                Shape::Line(line) => {
-                    let intersected = line.intersect(&self.tracer);
-                    self.intersect = Some(self.tracer);
-                    if intersected {
-                        return (Some(Shape::Line(*line)), Some(self.tracer));
-                    }
-                }
-                Shape::Circle(circle) => {
-                    let intersected = circle.intersect(&self.tracer);
-                    if intersected {
-                        self.intersect = Some(self.tracer);
-                        return (Some(Shape::Circle(*circle)), Some(self.tracer));
-                    }
-                }
-                Shape::Medium(medium) => {
-                    let intersected = medium.intersect(&self.tracer);
-                    if intersected {
-                        self.intersect = Some(self.tracer);
-                        return (Some(Shape::Medium(*medium)), Some(self.tracer));
-                    }
-                }
+    /// where the power went in the last trace
+    pub fn stats(&self) -> PowerStats {
+        self.stats
+    }
+
+    /// every time the last trace hit a detector
+    pub fn detector_hits(&self) -> &[DetectorHit] {
+        &self.detector_hits
+    }
+
+    /// where a ray goes after hitting a shape at `hit`, coming from `pos`, as directions with the share
+    /// of its power each one takes. empty when the surface soaks it up. what happens depends on the
+    /// shape's material, not on what kind of shape it is
+    ///
+    /// diffuse_rays: how many rays a diffuse surface scatters the ray into
+    pub fn bounce_angle(shape: &Shape, hit: &Hit, pos: Vec2, diffuse_rays: usize) -> Vec<(Vec2, f32)> {
+        let incoming = (hit.point - pos).normalize();
+        // the normal facing the incoming ray
+        let normal = if hit.normal.dot(incoming) > 0.0 { -hit.normal } else { hit.normal };
+        let reflected = Self::reflect(incoming, normal).normalize();
+        match shape.material() {
+            Material::Mirror => vec![(reflected, 1.0)],
+            Material::Absorber => vec![],
+            Material::Transparent => vec![(incoming, 1.0)],
+            Material::PartialMirror { reflectivity } => {
+                let reflectivity = reflectivity.clamp(0.0, 1.0);
+                vec![(reflected, reflectivity), (incoming, 1.0 - reflectivity)]
+            }
+            Material::Diffuse => {
+                // evenly spaced in the sine of the angle from the normal, so there are more rays
+                // close to the normal, like the cosine law
+                let count = diffuse_rays.max(1);
+                (0..count)
+                    .map(|i| {
+                        let sine = -1.0 + (2 * i + 1) as f32 / count as f32;
+                        let cosine = (1.0 - sine * sine).sqrt();
+                        (normal * cosine + normal.perp() * sine, 1.0 / count as f32)
+                    })
+                    .collect()
+            }
+            // refractive lines and circles are see-through. medium borders are crossed in
+            // `ray_trace_loop`, which knows the index on both sides
+            Material::Refractive => vec![(incoming, 1.0)],
+        }
+    }
+    /// finds the closest shapes in front of a ray.
+    ///
+    /// returns the index of every shape hit at the closest distance (usually one, more when mediums touch),
+    /// where it was hit and if the ray is leaving it (only true for mediums). one-sided shapes are
+    /// only hit from the front, rays coming from behind go right through them
+    ///
+    /// max_length: shapes further away than this are not hit
+    pub fn ray_trace(shapes: &[Shape], origin: Vec2, direction: Vec2, max_length: f32) -> Vec<(usize, Hit, bool)> {
+        let mut hits: Vec<(usize, Hit, bool)> = vec![];
+        for (index, shape) in shapes.iter().enumerate() {
+            let hit = shape
+                .ray_intersect(origin, direction)
+                .filter(|hit| hit.distance <= max_length)
+                .filter(|hit| shape.front(hit.point).is_none_or(|front| direction.dot(front) < 0.0));
+            if let Some(hit) = hit {
+                let is_leaving = match shape {
+                    Shape::Medium(_) => direction.dot(hit.normal) > 0.0,
+                    _ => false,
+                };
+                hits.push((index, hit, is_leaving));
             }
         }
-        return (None, Some(self.tracer));
+        let closest = hits.iter().map(|(_, hit, _)| hit.distance).fold(f32::MAX, f32::min);
+        hits.retain(|(_, hit, _)| hit.distance - closest <= EPSILON);
+        hits
     }
 
-    pub fn bounce_angle(shape: &Shape, point: Vec2, pos: Vec2, is_leaving: bool) -> Vec2 {
-        // let line_vector = line.point1 - line.point2;
-        let ray_vector = point - pos;
-        match shape {
-            Shape::Line(line) => {
+    /// the medium the ray is in. when mediums overlap the last one entered wins
+    fn current_medium<'a>(shapes: &'a [Shape], entered: &[usize]) -> Option<&'a Medium> {
+        match entered.last().map(|&index| &shapes[index]) {
+            Some(Shape::Medium(medium)) => Some(medium),
+            _ => None,
+        }
+    }
 
-                let normal_m = -1.0 / line.slope().unwrap_or(f32::MAX);
-                let normal_line = Line::equation_to_line(normal_m, line.intercept().unwrap_or(f32::MAX));
-                let normal_vector = normal_line.to_vector();
+    /// the refractive index the ray is in at a point
+    fn current_index(shapes: &[Shape], entered: &[usize], ambient_index: f32, wavelength: f32, point: Vec2) -> f32 {
+        Ray::current_medium(shapes, entered).map_or(ambient_index, |medium| medium.index_at(wavelength, point))
+    }
 
-                Self::reflect(ray_vector, normal_vector).normalize()
-            }
-            Shape::Circle(circle) => {
-                let normal = circle.normal(point);
-                Self::reflect(ray_vector, normal).normalize()
+    /// traces every branch until it bounced `scene.bounces` times, got absorbed, escaped
+    /// or carries less than `scene.min_power`.
+    ///
+    /// in graded mediums the ray curves, so it goes in steps of `scene.grin_step` and bends after each one
+    pub fn ray_trace_loop(&mut self, scene: &Scene) {
+        self.segments.clear();
+        self.stats = PowerStats::default();
+        self.detector_hits.clear();
+        let wavelength = self.wavelength.unwrap_or(REFERENCE_WAVELENGTH);
+
+        let entered: Vec<usize> = scene
+            .shapes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, shape)| match shape {
+                Shape::Medium(medium) if medium.material == Material::Refractive && medium.intersect(&self.start_position) => {
+                    Some(index)
+                }
+                _ => None,
+            })
+            .collect();
+        let mut branches = vec![Branch {
+            origin: self.start_position,
+            direction: self.start_direction.normalize(),
+            power: self.power,
+            entered,
+            depth: 0,
+            travelled: 0.0,
+        }];
 
+        while let Some(branch) = branches.pop() {
+            if branch.depth >= scene.bounces || branch.power < scene.min_power {
+                self.stats.untraced += branch.power;
+                continue;
             }
-            Shape::Medium(medium) => {
-                let normal = medium.normal_at_point(point);
-                let refractive_angle = if is_leaving {
-                    Medium::calculate_refractive_angle_two_mediums(1.5, 1.0,ray_vector, normal)
-                    
+            let reach = scene.max_length - branch.travelled;
+            let medium = Ray::current_medium(&scene.shapes, &branch.entered);
+            let graded = medium.filter(|medium| medium.is_graded());
+            // a midpoint step along the ray equation. the step goes straight along the direction
+            // halfway through it, the next one starts with the direction at its end
+            let (reach, direction, bent) = match graded {
+                Some(medium) => {
+                    let step = scene.grin_step.max(scene.max_length / MAX_GRIN_STEPS as f32).min(reach);
+                    let half = (branch.direction + medium.curvature(wavelength, branch.origin, branch.direction) * step / 2.0).normalize();
+                    let middle = branch.origin + half * step / 2.0;
+                    let bent = (branch.direction + medium.curvature(wavelength, middle, half) * step).normalize();
+                    (step, half, bent)
+                }
+                None => (reach, branch.direction, branch.direction),
+            };
+            let hits = Ray::ray_trace(&scene.shapes, branch.origin, direction, reach);
+            let end = hits.first().map_or(branch.origin + direction * reach, |(_, hit, _)| hit.point);
+            // the medium the branch is in soaks up some of it on the way
+            let power = medium.map_or(branch.power, |medium| {
+                branch.power * medium.transmittance(wavelength, end.distance(branch.origin))
+            });
+            self.stats.attenuated += branch.power - power;
+            self.segments.push(Segment {
+                start: branch.origin,
+                end,
+                power: branch.power,
+                end_power: power,
+            });
+            let Some(&(_, first_hit, _)) = hits.first() else {
+                // still inside the graded medium, keep curving
+                if graded.is_some() && branch.travelled + reach < scene.max_length {
+                    branches.push(Branch {
+                        origin: end,
+                        direction: bent,
+                        power,
+                        travelled: branch.travelled + reach,
+                        ..branch
+                    });
+                    continue;
+                }
+                if graded.is_some() {
+                    // out of length but still inside the medium
+                    self.stats.untraced += power;
                 } else {
-                    Medium::calculate_refractive_angle_two_mediums(1.0, 1.5,ray_vector, -normal)
-                };
-
-                // refractive_angle.unwrap_or(Self::reflect(ray_vector, normal).normalize()).normalize()
+                    // nothing left to hit, the branch escaped
+                    self.stats.escaped += power;
+                }
+                continue;
+            };
+            let next = |direction: Vec2, power: f32, entered: Vec<usize>| Branch {
+                origin: first_hit.point,
+                direction: direction.normalize(),
+                power,
+                entered,
+                depth: branch.depth + 1,
+                travelled: 0.0,
+            };
 
-                match refractive_angle {
-                    Some(angle) => angle,
-                    None => {
-                        Self::reflect(ray_vector, normal).normalize()},
+            for &(index, hit, _) in &hits {
+                if let Shape::Detector(detector) = &scene.shapes[index] {
+                    self.detector_hits.push(detector.record(index, &hit, direction, power, self.wavelength));
                 }
-                
-                 
             }
-        }
-    }
-    pub fn ray_trace(
-        &mut self,
-        step: f32,
-        shapes: &Vec<Shape>,
-        last_shape: &Option<Shape>,
-        is_inside_medium: bool,
-    ) -> (Option<Shape>, Vec2, bool) { // (shape, point_where_touch, is_leaving)
-        self.tracer = self.origin;
-        let step_dir = self.direction * step;
-        let mut last_shape = last_shape.clone();
-        // println!("{:?}", last_shape);
-        for _ in 0..(1000.0 / step) as usize {
-            self.tracer += step_dir;
-            let (shape, point) = self.touching_object(shapes);
-            if shape.is_some() {
-                if let Some(Shape::Medium(_)) = shape {
-                    if is_inside_medium {
-                        last_shape = shape;
-                        continue;
-                    }
+
+            // absorbers win over reflecting surfaces, those win over medium borders they lie on
+            let material = |index: usize| scene.shapes[index].material();
+            if hits.iter().any(|&(index, _, _)| material(index) == Material::Absorber) {
+                self.stats.absorbed += power;
+                continue;
+            }
+            if let Some(&(index, hit, _)) = hits.iter().find(|&&(index, _, _)| material(index).reflects()) {
+                let bounced = Ray::bounce_angle(&scene.shapes[index], &hit, branch.origin, scene.diffuse_rays);
+                // the first one is traced first
+                for (direction, share) in bounced.into_iter().rev() {
+                    branches.push(next(direction, power * share, branch.entered.clone()));
                 }
-                match shape.unwrap() {
-                    Shape::Line(line) => {
-                        if let Some(Shape::Line(last_line)) = last_shape {
-                            if line.compare(&last_line) {
-                                continue;
-                            }
-                        }
-                    }
-                    Shape::Circle(circle) => {
-                        if let Some(Shape::Circle(last_circle)) = last_shape {
+                continue;
+            }
 
-                            if circle.compare(&last_circle) {
-                                continue;
-                            }
-                        }
-                    }
-                    Shape::Medium(medium) => {
-                        if let Some(Shape::Medium(last_medium)) = last_shape {
-                            if medium.compare(&last_medium) {
-                                
-                                continue;
-                            }
-                        }
+            // only refractive borders are left, all at the same point, so cross all of them at once.
+            // refractive lines and circles and transparent shapes are see-through
+            let media_hits: Vec<&(usize, Hit, bool)> = hits
+                .iter()
+                .filter(|&&(index, _, _)| matches!(scene.shapes[index], Shape::Medium(_)) && material(index) == Material::Refractive)
+                .collect();
+            let Some(&&(_, hit, is_leaving)) = media_hits.first() else {
+                branches.push(next(direction, power, branch.entered.clone()));
+                continue;
+            };
+            let mut crossed = branch.entered.clone();
+            for &&(index, _, is_leaving) in &media_hits {
+                if is_leaving {
+                    if let Some(position) = crossed.iter().position(|&other| other == index) {
+                        crossed.remove(position);
                     }
+                } else {
+                    crossed.push(index);
                 }
-                return (shape, point.unwrap(), false);
-            } else {
-                // if is_inside_medium{continue;}
-                if let Some(Shape::Medium(last_medium)) = last_shape {
-                    // println!("leaving");
-                    return (Some(Shape::Medium(last_medium)), self.tracer, true);
+            }
+
+            let n1 = Ray::current_index(&scene.shapes, &branch.entered, scene.ambient_index, wavelength, hit.point);
+            let n2 = Ray::current_index(&scene.shapes, &crossed, scene.ambient_index, wavelength, hit.point);
+            // the normal facing the incoming ray
+            let normal = if is_leaving { -hit.normal } else { hit.normal };
+            let reflected = Ray::reflect(direction, normal);
+            match Medium::calculate_refractive_angle_two_mediums(n1, n2, direction, normal) {
+                // total internal reflection keeps the ray on the same side
+                None => branches.push(next(reflected, power, branch.entered.clone())),
+                Some(refracted) if !scene.fresnel => branches.push(next(refracted, power, crossed)),
+                Some(refracted) => {
+                    let reflectance = Medium::fresnel_reflectance(n1, n2, direction, normal);
+                    // pushed last so the transmitted part is traced first
+                    branches.push(next(reflected, power * reflectance, branch.entered.clone()));
+                    branches.push(next(refracted, power * (1.0 - reflectance), crossed));
                 }
             }
         }
-        return (None, self.tracer, false);
-    }
-
-    pub fn ray_trace_loop(&mut self, bounces: u32, shapes: &Vec<Shape>) {
-        self.origin = self.start_position;
-        self.tracer = self.origin;
-        self.points_draw.clear();
-        self.points_draw.push(self.origin);
-        self.direction = self.start_direction;
-        let mut is_inside_medium = false;
-
-        let mut last_shape = None;
-        for _ in 0..bounces as usize {
-            let (shape, point, is_leaving) = self.ray_trace(0.1, shapes, &last_shape, is_inside_medium);
-            if let Some(shape) = &shape {
-                let bounce_normal = Ray::bounce_angle(shape, self.tracer, self.origin, is_leaving);
-                self.direction = bounce_normal;
-            }
-            if is_leaving {
-                last_shape = None;
-            } else {
-                last_shape = shape;
+    }
+}
+
+impl Shape {
+    pub fn material(&self) -> Material {
+        match self {
+            Shape::Line(line) => line.material,
+            Shape::Circle(circle) => circle.material,
+            Shape::Medium(medium) => medium.material,
+            Shape::Mirror(mirror) => mirror.material,
+            Shape::Detector(detector) => detector.material,
+        }
+    }
 
+    /// which way the front of a one-sided shape faces at a point on it in the world. None when rays
+    /// hit it from both sides
+    pub fn front(&self, point: Vec2) -> Option<Vec2> {
+        match self {
+            Shape::Line(line) if line.one_sided => Some(line.to_world().normal()),
+            Shape::Mirror(mirror) if mirror.one_sided => Some(mirror.front(point)),
+            _ => None,
+        }
+    }
+
+    /// the corners of a box around the shape
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        match self {
+            Shape::Line(line) => {
+                let line = line.to_world();
+                (line.start.min(line.end), line.start.max(line.end))
+            }
+            Shape::Circle(circle) => {
+                let (center, radius) = (circle.center(), circle.radius * circle.transform.scale);
+                (center - Vec2::splat(radius), center + Vec2::splat(radius))
+            }
+            Shape::Medium(medium) => medium.bounds(),
+            Shape::Mirror(mirror) => mirror.bounds(),
+            Shape::Detector(detector) => {
+                let (start, end) = detector.ends();
+                (start.min(end), start.max(end))
             }
-            is_inside_medium = !is_leaving;
-            self.points_draw.push(self.tracer);
-            self.origin = point;
-            // println!("{:?}", is_leaving);
+        }
+    }
+
+    pub fn transform(&self) -> Transform {
+        match self {
+            Shape::Line(line) => line.transform,
+            Shape::Circle(circle) => circle.transform,
+            Shape::Medium(medium) => medium.transform,
+            Shape::Mirror(mirror) => mirror.transform,
+            Shape::Detector(detector) => detector.transform,
+        }
+    }
+
+    pub fn transform_mut(&mut self) -> &mut Transform {
+        match self {
+            Shape::Line(line) => &mut line.transform,
+            Shape::Circle(circle) => &mut circle.transform,
+            Shape::Medium(medium) => &mut medium.transform,
+            Shape::Mirror(mirror) => &mut mirror.transform,
+            Shape::Detector(detector) => &mut detector.transform,
+        }
+    }
+
+    pub fn ray_intersect(&self, origin: Vec2, direction: Vec2) -> Option<Hit> {
+        match self {
+            Shape::Line(line) => line.ray_intersect(origin, direction),
+            Shape::Circle(circle) => circle.ray_intersect(origin, direction),
+            Shape::Medium(medium) => medium.ray_intersect(origin, direction),
+            Shape::Mirror(mirror) => mirror.ray_intersect(origin, direction),
+            Shape::Detector(detector) => detector.ray_intersect(origin, direction),
         }
     }
 }
@@ -208,7 +427,45 @@ impl Ray {
     }
 }
 
+impl PowerStats {
+    pub fn total(&self) -> f32 {
+        self.absorbed + self.attenuated + self.escaped + self.untraced
+    }
+}
+
+impl AddAssign for PowerStats {
+    fn add_assign(&mut self, other: PowerStats) {
+        self.absorbed += other.absorbed;
+        self.attenuated += other.attenuated;
+        self.escaped += other.escaped;
+        self.untraced += other.untraced;
+    }
+}
+
+/// the geometry is worked out in the shape's own coordinates, `intersect` and `ray_intersect`
+/// take world coordinates and go through the shape's transform
 pub trait Shape_Util {
     fn compare(&self, other: &Self) -> bool;
-    fn intersect(&self, point: &Vec2) -> bool;
+    fn transform(&self) -> Transform;
+    /// point: in the shape's coordinates
+    fn local_intersect(&self, point: &Vec2) -> bool;
+    /// like `ray_intersect`, with everything in the shape's coordinates
+    fn local_ray_intersect(&self, origin: Vec2, direction: Vec2) -> Option<Hit>;
+
+    fn intersect(&self, point: &Vec2) -> bool {
+        self.local_intersect(&self.transform().to_local(*point))
+    }
+    /// finds the closest hit in front of a ray.
+    ///
+    /// direction: should be normalized
+    fn ray_intersect(&self, origin: Vec2, direction: Vec2) -> Option<Hit> {
+        let transform = self.transform();
+        // turning keeps the direction normalized, so distances only change by the scale
+        let hit = self.local_ray_intersect(transform.to_local(origin), transform.direction_to_local(direction))?;
+        Some(Hit {
+            distance: hit.distance * transform.scale,
+            point: transform.to_world(hit.point),
+            normal: transform.direction_to_world(hit.normal),
+        })
+    }
 }
@@ -0,0 +1,144 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::detectors::DetectorHit;
+use crate::lights::Light;
+use crate::mediums::AIR;
+use crate::rays::{PowerStats, Ray, Shape, MAX_DISTANCE};
+
+/// default for the power a ray needs to keep being traced
+pub const MIN_POWER: f32 = 0.01;
+/// default for the step length in graded mediums
+pub const GRIN_STEP: f32 = 2.0;
+/// the most steps a ray takes through graded mediums between bounces. shorter steps than that,
+/// zero or negative ones are stretched so the ray still gets through `max_length`
+pub const MAX_GRIN_STEPS: usize = 2000;
+/// default for how many rays a diffuse surface scatters a ray into
+pub const DIFFUSE_RAYS: usize = 8;
+
+/// everything rays can hit, the light they come from and how they are traced.
+///
+/// saved as RON so scene files can be edited by hand. missing fields get their default
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scene {
+    pub shapes: Vec<Shape>,
+    /// refractive index of the space around the mediums
+    pub ambient_index: f32,
+    pub light: Light,
+    /// how many times a ray can bounce
+    pub bounces: u32,
+    /// how far a ray goes between bounces
+    pub max_length: f32,
+    /// split rays into a reflected and a transmitted part at medium borders
+    pub fresnel: bool,
+    /// rays with less power than this are not traced any further
+    pub min_power: f32,
+    /// how far a ray goes in one step through a medium with a gradient, smaller curves smoother
+    pub grin_step: f32,
+    /// how many rays a diffuse surface scatters a ray into, they share its power
+    pub diffuse_rays: usize,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(error) => write!(f, "couldn't access the scene file: {}", error),
+            SceneError::Parse(error) => write!(f, "couldn't read the scene: {}", error),
+            SceneError::Serialize(error) => write!(f, "couldn't write the scene: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Scene::new(vec![])
+    }
+}
+
+impl Scene {
+    pub fn new(shapes: Vec<Shape>) -> Self {
+        Scene {
+            shapes,
+            ambient_index: AIR,
+            light: Light::default(),
+            bounces: 10,
+            max_length: MAX_DISTANCE,
+            fresnel: true,
+            min_power: MIN_POWER,
+            grin_step: GRIN_STEP,
+            diffuse_rays: DIFFUSE_RAYS,
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Scene, SceneError> {
+        let text = fs::read_to_string(path).map_err(SceneError::Io)?;
+        Scene::from_ron(&text)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SceneError> {
+        fs::write(path, self.to_ron()?).map_err(SceneError::Io)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Scene, SceneError> {
+        ron::from_str(text).map_err(SceneError::Parse)
+    }
+
+    pub fn to_ron(&self) -> Result<String, SceneError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()).map_err(SceneError::Serialize)
+    }
+
+    /// the corners of a box around every shape and the light
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        self.shapes
+            .iter()
+            .map(|shape| shape.bounds())
+            .fold((self.light.position, self.light.position), |(min, max), (shape_min, shape_max)| {
+                (min.min(shape_min), max.max(shape_max))
+            })
+    }
+
+    pub fn trace(&self, ray: &mut Ray) {
+        ray.ray_trace_loop(self);
+    }
+
+    /// makes the light's rays and traces them
+    pub fn trace_light(&self) -> Vec<Ray> {
+        let mut rays = self.light.rays();
+        for ray in rays.iter_mut() {
+            self.trace(ray);
+        }
+        rays
+    }
+
+    /// where the power of all the rays went
+    pub fn stats(rays: &[Ray]) -> PowerStats {
+        let mut stats = PowerStats::default();
+        for ray in rays {
+            stats += ray.stats();
+        }
+        stats
+    }
+
+    /// every hit of all the rays on the detector at `index` in the shapes
+    pub fn detector_hits(rays: &[Ray], index: usize) -> Vec<DetectorHit> {
+        rays.iter()
+            .flat_map(|ray| ray.detector_hits())
+            .filter(|hit| hit.detector == index)
+            .copied()
+            .collect()
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::color::Color;
+
+/// wavelength in nanometers that refractive indices are usually given for, the sodium D line
+pub const REFERENCE_WAVELENGTH: f32 = 589.3;
+/// shortest wavelength white light is split into, in nanometers
+pub const VISIBLE_MIN: f32 = 400.0;
+/// longest wavelength white light is split into, in nanometers
+pub const VISIBLE_MAX: f32 = 700.0;
+/// wavelengths in nanometers that stand for blue, green and red light
+pub const BLUE: f32 = 450.0;
+pub const GREEN: f32 = 550.0;
+pub const RED: f32 = 650.0;
+
+/// what light a source sends out
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Spectrum {
+    /// no particular wavelength. traced at `REFERENCE_WAVELENGTH` and drawn black
+    #[default]
+    Plain,
+    /// one wavelength
+    Single,
+    /// several wavelengths spread over the visible spectrum
+    White,
+}
+
+/// `count` wavelengths spread evenly from `VISIBLE_MIN` to `VISIBLE_MAX`
+pub fn visible_wavelengths(count: usize) -> Vec<f32> {
+    if count == 1 {
+        return vec![(VISIBLE_MIN + VISIBLE_MAX) / 2.0];
+    }
+    (0..count)
+        .map(|i| VISIBLE_MIN + (VISIBLE_MAX - VISIBLE_MIN) * i as f32 / (count - 1) as f32)
+        .collect()
+}
+
+/// the color of light with a wavelength in nanometers, black outside of 380-780.
+///
+/// https://www.physics.sfasu.edu/astro/color/spectra.html
+pub fn wavelength_to_color(wavelength: f32) -> Color {
+    let (red, green, blue) = match wavelength {
+        w if (380.0..440.0).contains(&w) => ((440.0 - w) / (440.0 - 380.0), 0.0, 1.0),
+        w if (440.0..490.0).contains(&w) => (0.0, (w - 440.0) / (490.0 - 440.0), 1.0),
+        w if (490.0..510.0).contains(&w) => (0.0, 1.0, (510.0 - w) / (510.0 - 490.0)),
+        w if (510.0..580.0).contains(&w) => ((w - 510.0) / (580.0 - 510.0), 1.0, 0.0),
+        w if (580.0..645.0).contains(&w) => (1.0, (645.0 - w) / (645.0 - 580.0), 0.0),
+        w if (645.0..=780.0).contains(&w) => (1.0, 0.0, 0.0),
+        _ => (0.0, 0.0, 0.0),
+    };
+    // the eye sees less at the ends of the spectrum
+    let intensity = match wavelength {
+        w if (380.0..420.0).contains(&w) => 0.3 + 0.7 * (w - 380.0) / (420.0 - 380.0),
+        w if (420.0..700.0).contains(&w) => 1.0,
+        w if (700.0..=780.0).contains(&w) => 0.3 + 0.7 * (780.0 - w) / (780.0 - 700.0),
+        _ => 0.0,
+    };
+    Color::new(red * intensity, green * intensity, blue * intensity, 1.0)
+}
@@ -0,0 +1,51 @@
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// places a shape in the world: scaled, then turned around its own origin, then moved.
+/// shapes keep their points in their own coordinates and this takes them to the world
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Transform {
+    pub translation: Vec2,
+    /// counterclockwise, in radians
+    pub rotation: f32,
+    /// the same in every direction
+    pub scale: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            translation: Vec2::ZERO,
+            rotation: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Transform {
+    /// turns a vector by `angle` radians counterclockwise
+    fn rotate(vector: Vec2, angle: f32) -> Vec2 {
+        let (sin, cos) = angle.sin_cos();
+        vec2(cos * vector.x - sin * vector.y, sin * vector.x + cos * vector.y)
+    }
+    pub fn to_world(&self, point: Vec2) -> Vec2 {
+        self.translation + Transform::rotate(point * self.scale, self.rotation)
+    }
+    pub fn to_local(&self, point: Vec2) -> Vec2 {
+        Transform::rotate(point - self.translation, -self.rotation) / self.scale
+    }
+    /// turns a direction or a normal into the world, keeping its length
+    pub fn direction_to_world(&self, direction: Vec2) -> Vec2 {
+        Transform::rotate(direction, self.rotation)
+    }
+    /// turns a direction or a normal into the shape's coordinates, keeping its length
+    pub fn direction_to_local(&self, direction: Vec2) -> Vec2 {
+        Transform::rotate(direction, -self.rotation)
+    }
+    /// turns the shape by `angle` radians around a point in the world
+    pub fn rotate_about(&mut self, pivot: Vec2, angle: f32) {
+        self.translation = pivot + Transform::rotate(self.translation - pivot, angle);
+        self.rotation += angle;
+    }
+}
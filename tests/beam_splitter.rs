@@ -0,0 +1,71 @@
+use std::f32::consts::PI;
+
+use glam::{vec2, Vec2};
+use ray_tracing::lines::Line;
+use ray_tracing::mirrors::Mirror;
+use ray_tracing::rays::Shape;
+use ray_tracing::scene::Scene;
+
+mod common;
+use common::{close, direction, trace};
+
+/// enough bounces to get through the splitter, to a mirror and back
+fn scene(shapes: Vec<Shape>) -> Scene {
+    let mut scene = Scene::new(shapes);
+    scene.bounces = 4;
+    scene
+}
+
+#[test]
+fn splitter_sends_part_on_and_part_sideways() {
+    let splitter = Line::beam_splitter(vec2(-50.0, -50.0), vec2(50.0, 50.0), 0.3);
+    let ray = trace(&scene(vec![Shape::Line(splitter)]), vec2(-100.0, 0.0), vec2(1.0, 0.0));
+    let segments = ray.segments();
+    assert!(close(segments[0].end, Vec2::ZERO));
+    let children: Vec<_> = segments.iter().filter(|segment| close(segment.start, Vec2::ZERO)).collect();
+    assert_eq!(children.len(), 2);
+    let reflected = children.iter().find(|segment| segment.end.y > 0.0).unwrap();
+    let transmitted = children.iter().find(|segment| segment.end.x > 0.0).unwrap();
+    assert!(close(direction(reflected), vec2(0.0, 1.0)));
+    assert!((reflected.power - 0.3).abs() < 0.001);
+    assert!((transmitted.power - 0.7).abs() < 0.001);
+    assert!((ray.stats().escaped - 1.0).abs() < 0.001);
+}
+
+#[test]
+fn michelson_arms_come_back_to_the_splitter() {
+    let shapes = vec![
+        Shape::Line(Line::beam_splitter(vec2(-50.0, -50.0), vec2(50.0, 50.0), 0.5)),
+        Shape::Line(Line::from(vec2(-50.0, 200.0), vec2(50.0, 200.0), 1.0)),
+        Shape::Line(Line::from(vec2(300.0, -50.0), vec2(300.0, 50.0), 1.0)),
+    ];
+    let ray = trace(&scene(shapes), vec2(-100.0, 0.0), vec2(1.0, 0.0));
+    // both arms return to the splitter, and half of each goes down to the screen side
+    let down: Vec<_> = ray.segments().iter().filter(|segment| close(segment.start, Vec2::ZERO) && segment.end.y < -1.0).collect();
+    assert_eq!(down.len(), 2);
+    assert!(down.iter().all(|segment| (segment.power - 0.25).abs() < 0.001));
+}
+
+#[test]
+fn one_sided_line_only_reflects_from_the_front() {
+    let mut line = Line::from(vec2(100.0, -50.0), vec2(100.0, 50.0), 1.0);
+    line.one_sided = true;
+    // the front is on the left going up, towards -x
+    let front = trace(&scene(vec![Shape::Line(line)]), Vec2::ZERO, vec2(1.0, 0.0));
+    assert!(front.segments()[1].end.x < 100.0);
+    let back = trace(&scene(vec![Shape::Line(line)]), vec2(200.0, 0.0), vec2(-1.0, 0.0));
+    assert_eq!(back.segments().len(), 1);
+    assert!(back.segments()[0].end.x < 0.0);
+}
+
+#[test]
+fn one_sided_arc_faces_its_center() {
+    let mut mirror = Mirror::arc(Vec2::ZERO, 100.0, -PI / 4.0, PI / 4.0);
+    mirror.one_sided = true;
+    assert!(close(mirror.front(vec2(100.0, 0.0)), vec2(-1.0, 0.0)));
+    let inside = trace(&scene(vec![Shape::Mirror(mirror)]), Vec2::ZERO, vec2(1.0, 0.0));
+    assert!(close(inside.segments()[0].end, vec2(100.0, 0.0)));
+    assert!(inside.segments()[1].end.x < 100.0);
+    let outside = trace(&scene(vec![Shape::Mirror(mirror)]), vec2(200.0, 0.0), vec2(-1.0, 0.0));
+    assert_eq!(outside.segments().len(), 1);
+}
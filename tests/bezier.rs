@@ -0,0 +1,89 @@
+use glam::{vec2, Vec2};
+use ray_tracing::bezier::Bezier;
+use ray_tracing::editor::Editor;
+use ray_tracing::mediums::{Medium, Side, GLASS};
+use ray_tracing::mirrors::Mirror;
+use ray_tracing::rays::{Shape, Shape_Util};
+use ray_tracing::scene::Scene;
+
+mod common;
+use common::{blue, trace};
+
+/// a square with its right side bulging out
+fn bulging_box() -> Medium {
+    let mut medium = Medium::new(vec2(0.0, 0.0), vec2(100.0, 100.0), GLASS, blue());
+    medium.sides = vec![Side::Straight, Side::Bezier { controls: [vec2(140.0, 30.0), vec2(140.0, 70.0)] }];
+    medium
+}
+
+#[test]
+fn straight_curve_is_hit_like_a_line() {
+    let curve = Bezier([vec2(0.0, 0.0), vec2(10.0, 10.0), vec2(20.0, 20.0), vec2(30.0, 30.0)]);
+    let hits = curve.ray_hits(vec2(0.0, 20.0), vec2(1.0, 0.0));
+    assert_eq!(hits.len(), 1);
+    let (distance, t) = hits[0];
+    assert!((distance - 20.0).abs() < 0.001);
+    assert!((curve.point(t) - vec2(20.0, 20.0)).length() < 0.001);
+    assert!(curve.ray_hits(vec2(0.0, 40.0), vec2(1.0, 0.0)).is_empty());
+}
+
+#[test]
+fn curve_mirror_shaped_like_a_parabola_focuses() {
+    // a quadratic Bézier is a parabola, raised to a cubic one
+    let (focal_length, half) = (50.0, 80.0);
+    let top = vec2(half * half / (4.0 * focal_length), half);
+    let bottom = vec2(top.x, -half);
+    let middle = vec2(-top.x, 0.0);
+    let mirror = Mirror::bezier([bottom, bottom.lerp(middle, 2.0 / 3.0), top.lerp(middle, 2.0 / 3.0), top]);
+
+    for height in [-60.0, -20.0, 30.0, 70.0] {
+        let mut scene = Scene::new(vec![Shape::Mirror(mirror)]);
+        scene.bounces = 2;
+        let ray = trace(&scene, vec2(300.0, height), vec2(-1.0, 0.0));
+        let bounced = ray.segments()[1];
+        let direction = (bounced.end - bounced.start).normalize();
+        let miss = direction.perp_dot(vec2(focal_length, 0.0) - bounced.start).abs();
+        assert!(miss < 0.05, "{} misses by {}", height, miss);
+    }
+}
+
+#[test]
+fn bezier_side_bulges_out_of_the_medium() {
+    let medium = bulging_box();
+    assert!(medium.signed_area() > 100.0 * 100.0 + 1000.0);
+    assert!(medium.intersect(&vec2(120.0, 50.0)));
+    assert!(!medium.intersect(&vec2(135.0, 5.0)));
+    assert!(medium.intersect(&vec2(50.0, 50.0)));
+
+    let hit = medium.ray_intersect(vec2(300.0, 50.0), vec2(-1.0, 0.0)).unwrap();
+    assert!((hit.point - vec2(130.0, 50.0)).length() < 0.01);
+    assert!((hit.normal - vec2(1.0, 0.0)).length() < 0.001);
+    // leaving through the curve from inside
+    let hit = medium.ray_intersect(vec2(50.0, 50.0), vec2(1.0, 0.0)).unwrap();
+    assert!((hit.point.x - 130.0).abs() < 0.01);
+    assert!(hit.normal.x > 0.99);
+}
+
+#[test]
+fn bezier_side_control_points_are_handles() {
+    let mut shapes = vec![Shape::Medium(bulging_box())];
+    let mut editor = Editor::default();
+    editor.selected = Some(0);
+    // four corners, two control points and the rotation handle
+    assert_eq!(shapes[0].handles().len(), 7);
+
+    editor.press(&shapes, vec2(140.0, 30.0));
+    editor.drag(&mut shapes, vec2(160.0, 20.0));
+    let Shape::Medium(medium) = &shapes[0] else { panic!() };
+    assert_eq!(medium.side(1), Side::Bezier { controls: [vec2(160.0, 20.0), vec2(140.0, 70.0)] });
+    assert_eq!(medium.points[1], vec2(100.0, 0.0));
+}
+
+#[test]
+fn bezier_mirror_bounces_at_right_angles_to_the_curve() {
+    let mirror = Mirror::bezier([vec2(0.0, -50.0), vec2(0.0, -20.0), vec2(0.0, 20.0), vec2(0.0, 50.0)]);
+    let hit = mirror.ray_intersect(vec2(-100.0, 10.0), vec2(1.0, 0.0)).unwrap();
+    assert!((hit.point - vec2(0.0, 10.0)).length() < 0.001);
+    assert!((hit.normal - vec2(-1.0, 0.0)).length() < 0.001);
+    assert!((mirror.normal(hit.point).abs() - Vec2::X).length() < 0.001);
+}
@@ -0,0 +1,38 @@
+use glam::vec2;
+use ray_tracing::camera::Camera;
+
+mod common;
+use common::close;
+
+#[test]
+fn screen_and_world_round_trip() {
+    let camera = Camera {
+        position: vec2(100.0, -50.0),
+        zoom: 2.5,
+    };
+    let world = vec2(30.0, 40.0);
+    assert!(close(camera.to_world(camera.to_screen(world)), world));
+}
+
+#[test]
+fn zoom_keeps_the_point_under_the_mouse() {
+    let mut camera = Camera::default();
+    let mouse = vec2(120.0, 80.0);
+    let before = camera.to_world(mouse);
+
+    camera.zoom_at(mouse, 3.0);
+
+    assert!(close(camera.to_world(mouse), before));
+}
+
+#[test]
+fn fit_shows_the_whole_box() {
+    let mut camera = Camera::default();
+    let window = vec2(800.0, 600.0);
+    camera.fit(vec2(-1000.0, 0.0), vec2(1000.0, 500.0), window);
+
+    for corner in [vec2(-1000.0, 0.0), vec2(1000.0, 500.0)] {
+        let screen = camera.to_screen(corner);
+        assert!(screen.x.abs() <= window.x / 2.0 && screen.y.abs() <= window.y / 2.0);
+    }
+}
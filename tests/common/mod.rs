@@ -0,0 +1,35 @@
+//! helpers shared by the integration tests. each test file only uses some of them
+#![allow(dead_code)]
+
+use glam::Vec2;
+use ray_tracing::color::Color;
+use ray_tracing::rays::{Ray, Segment, Shape};
+use ray_tracing::scene::Scene;
+
+pub fn close(a: Vec2, b: Vec2) -> bool {
+    (a - b).length() < 0.001
+}
+
+/// the see-through blue the test mediums are drawn in
+pub fn blue() -> Color {
+    Color::new(0.0, 0.0, 1.0, 0.5)
+}
+
+/// which way a segment goes
+pub fn direction(segment: &Segment) -> Vec2 {
+    (segment.end - segment.start).normalize()
+}
+
+/// traces one ray from `start` through the scene
+pub fn trace(scene: &Scene, start: Vec2, direction: Vec2) -> Ray {
+    let mut ray = Ray::new(start, direction, direction);
+    scene.trace(&mut ray);
+    ray
+}
+
+/// a scene that doesn't split rays at medium borders, so each ray is one path that's easy to follow
+pub fn without_fresnel(shapes: Vec<Shape>) -> Scene {
+    let mut scene = Scene::new(shapes);
+    scene.fresnel = false;
+    scene
+}
@@ -0,0 +1,71 @@
+use glam::{vec2, Vec2};
+use ray_tracing::detectors::{Detector, Profile};
+use ray_tracing::materials::Material;
+use ray_tracing::rays::{Ray, Shape};
+use ray_tracing::scene::Scene;
+
+mod common;
+use common::trace;
+
+/// a screen at x = 100 going up from y = -50
+fn screen() -> Detector {
+    Detector::new(vec2(100.0, -50.0), vec2(100.0, 50.0))
+}
+
+#[test]
+fn records_where_and_how_a_ray_hits() {
+    let scene = Scene::new(vec![Shape::Detector(screen())]);
+    let mut ray = Ray::new(vec2(0.0, 10.0), vec2(1.0, 0.0), vec2(1.0, 0.0));
+    ray.wavelength = Some(500.0);
+    scene.trace(&mut ray);
+    let hits = ray.detector_hits();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].detector, 0);
+    assert!((hits[0].position - 60.0).abs() < 0.001);
+    assert!(hits[0].angle.abs() < 0.001);
+    assert!((hits[0].power - 1.0).abs() < 0.001);
+    assert_eq!(hits[0].wavelength, Some(500.0));
+
+    // going up towards the end is a positive angle
+    let slanted = trace(&scene, vec2(50.0, -20.0), vec2(1.0, 1.0).normalize());
+    assert!((slanted.detector_hits()[0].angle - 45.0_f32.to_radians()).abs() < 0.001);
+}
+
+#[test]
+fn absorbs_unless_transparent() {
+    let scene = Scene::new(vec![Shape::Detector(screen())]);
+    let ray = trace(&scene, Vec2::ZERO, vec2(1.0, 0.0));
+    assert!((ray.stats().absorbed - 1.0).abs() < 0.001);
+
+    let mut probe = screen();
+    probe.material = Material::Transparent;
+    let ray = trace(&Scene::new(vec![Shape::Detector(probe)]), Vec2::ZERO, vec2(1.0, 0.0));
+    assert_eq!(ray.detector_hits().len(), 1);
+    assert!((ray.stats().escaped - 1.0).abs() < 0.001);
+}
+
+#[test]
+fn histogram_bins_the_power_along_it() {
+    let mut detector = screen();
+    detector.bins = 4;
+    let scene = Scene::new(vec![Shape::Detector(detector)]);
+    let rays: Vec<Ray> = [-40.0, -30.0, 30.0].iter().map(|&y| trace(&scene, vec2(0.0, y), vec2(1.0, 0.0))).collect();
+    let hits = Scene::detector_hits(&rays, 0);
+    assert_eq!(hits.len(), 3);
+    // 25 long bins, so power per length
+    let histogram = detector.histogram(&hits);
+    assert_eq!(histogram.len(), 4);
+    assert!((histogram[0] - 2.0 / 25.0).abs() < 0.001);
+    assert!(histogram[1].abs() < 0.001);
+    assert!((histogram[3] - 1.0 / 25.0).abs() < 0.001);
+}
+
+#[test]
+fn profile_measures_the_spread() {
+    let scene = Scene::new(vec![Shape::Detector(screen())]);
+    let rays: Vec<Ray> = [-10.0, 10.0].iter().map(|&y| trace(&scene, vec2(0.0, y), vec2(1.0, 0.0))).collect();
+    let profile = Profile::of(&Scene::detector_hits(&rays, 0));
+    assert!((profile.power - 2.0).abs() < 0.001);
+    assert!((profile.center - 50.0).abs() < 0.001);
+    assert!((profile.width - 10.0).abs() < 0.001);
+}
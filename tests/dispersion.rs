@@ -0,0 +1,79 @@
+use glam::vec2;
+use ray_tracing::color::Color;
+use ray_tracing::lights::LightKind;
+use ray_tracing::mediums::{Medium, RefractiveIndex, CROWN_GLASS, FLINT_GLASS};
+use ray_tracing::rays::Shape;
+use ray_tracing::scene::Scene;
+use ray_tracing::spectrum::{self, Spectrum, REFERENCE_WAVELENGTH};
+
+mod common;
+use common::blue;
+
+#[test]
+fn sellmeier_glass_matches_its_catalog_index() {
+    assert!((CROWN_GLASS.at(REFERENCE_WAVELENGTH) - 1.5168).abs() < 0.001);
+    assert!((FLINT_GLASS.at(REFERENCE_WAVELENGTH) - 1.7847).abs() < 0.001);
+}
+
+#[test]
+fn blue_bends_more_than_red() {
+    for index in [CROWN_GLASS, FLINT_GLASS, RefractiveIndex::Cauchy { a: 1.5, b: 0.0042 }] {
+        assert!(index.at(400.0) > index.at(700.0));
+    }
+    assert_eq!(RefractiveIndex::Constant(1.5).at(400.0), 1.5);
+}
+
+#[test]
+fn white_light_splits_into_colors() {
+    let mut scene = Scene::new(vec![Shape::Medium(Medium::new(
+        vec2(-500.0, -100.0),
+        vec2(500.0, 100.0),
+        FLINT_GLASS,
+        blue(),
+    ))]);
+    scene.bounces = 2;
+    scene.fresnel = false;
+    scene.light.kind = LightKind::SingleRay;
+    scene.light.position = vec2(0.0, -200.0);
+    scene.light.direction = vec2(1.0, 1.0).normalize();
+    scene.light.spectrum = Spectrum::White;
+    scene.light.colors = 5;
+    let rays = scene.trace_light();
+
+    assert_eq!(rays.len(), 5);
+    assert!(rays.iter().all(|ray| (ray.power - 0.2).abs() < 0.001));
+    // shortest wavelength first, each one bent more towards the normal than the next
+    let slopes: Vec<f32> = rays
+        .iter()
+        .map(|ray| {
+            let inside = ray.segments()[1];
+            (inside.end - inside.start).normalize().x
+        })
+        .collect();
+    assert!(slopes.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn wavelengths_have_their_colors() {
+    let red = spectrum::wavelength_to_color(700.0);
+    let blue = spectrum::wavelength_to_color(450.0);
+    assert!(red.red > red.blue);
+    assert!(blue.blue > blue.red);
+    assert_eq!(spectrum::wavelength_to_color(1000.0), Color::new(0.0, 0.0, 0.0, 1.0));
+    assert_eq!(spectrum::visible_wavelengths(3), vec![400.0, 550.0, 700.0]);
+}
+
+#[test]
+fn scene_files_keep_constant_indices_as_numbers() {
+    let text = "(shapes: [Medium((min: (0.0, 0.0), max: (1.0, 1.0), refractive_index: 1.5, color: (red: 0.0, green: 0.0, blue: 1.0, alpha: 0.5)))])";
+    let scene = Scene::from_ron(text).unwrap();
+    let Shape::Medium(medium) = &scene.shapes[0] else {
+        panic!("expected a medium");
+    };
+    assert_eq!(medium.refractive_index(), RefractiveIndex::Constant(1.5));
+
+    let mut dispersive = medium.clone();
+    dispersive.set_refractive_index(CROWN_GLASS);
+    let scene = Scene::new(vec![Shape::Medium(dispersive)]);
+    assert_eq!(Scene::from_ron(&scene.to_ron().unwrap()).unwrap(), scene);
+}
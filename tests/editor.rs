@@ -0,0 +1,52 @@
+use glam::vec2;
+use ray_tracing::editor::Editor;
+use ray_tracing::lines::Line;
+use ray_tracing::mediums::{Medium, GLASS};
+use ray_tracing::rays::Shape;
+
+mod common;
+use common::blue;
+
+#[test]
+fn dragging_a_line_end_moves_only_that_end() {
+    let mut shapes = vec![Shape::Line(Line::from(vec2(0.0, 0.0), vec2(100.0, 0.0), 1.0))];
+    let mut editor = Editor::default();
+
+    editor.press(&shapes, vec2(101.0, 1.0));
+    editor.drag(&mut shapes, vec2(100.0, 50.0));
+    editor.release();
+
+    let Shape::Line(line) = shapes[0] else { panic!() };
+    assert_eq!(line.start, vec2(0.0, 0.0));
+    assert_eq!(line.end, vec2(100.0, 50.0));
+}
+
+#[test]
+fn dragging_a_medium_corner_moves_only_that_point() {
+    let medium = Medium::new(vec2(0.0, 0.0), vec2(100.0, 100.0), GLASS, blue());
+    let mut shapes = vec![Shape::Medium(medium)];
+    let mut editor = Editor::default();
+
+    // drag the bottom left corner past the top right one
+    editor.press(&shapes, vec2(0.0, 0.0));
+    editor.drag(&mut shapes, vec2(150.0, 120.0));
+    editor.drag(&mut shapes, vec2(150.0, 150.0));
+
+    let Shape::Medium(medium) = &shapes[0] else { panic!() };
+    assert_eq!(medium.points, vec![vec2(150.0, 150.0), vec2(100.0, 0.0), vec2(100.0, 100.0), vec2(0.0, 100.0)]);
+}
+
+#[test]
+fn delete_removes_the_selected_shape() {
+    let mut shapes = vec![
+        Shape::Line(Line::from(vec2(0.0, 0.0), vec2(100.0, 0.0), 1.0)),
+        Shape::Line(Line::from(vec2(0.0, 50.0), vec2(100.0, 50.0), 1.0)),
+    ];
+    let mut editor = Editor::default();
+
+    editor.press(&shapes, vec2(50.0, 50.0));
+    editor.delete_selected(&mut shapes);
+
+    assert_eq!(shapes.len(), 1);
+    assert!(editor.selected.is_none());
+}
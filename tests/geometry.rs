@@ -0,0 +1,106 @@
+use glam::vec2;
+use ray_tracing::editor::Editor;
+use ray_tracing::geometry::{closest_point_on_segment, distance_to_segment, ray_segment_intersection, segment_intersection, side};
+use ray_tracing::lines::Line;
+use ray_tracing::rays::Shape;
+
+mod common;
+use common::close;
+
+#[test]
+fn distance_to_segment_is_perpendicular_in_the_middle() {
+    let (start, end) = (vec2(0.0, 0.0), vec2(100.0, 0.0));
+    assert_eq!(distance_to_segment(start, end, vec2(30.0, 40.0)), 40.0);
+    assert_eq!(distance_to_segment(start, end, vec2(30.0, -7.0)), 7.0);
+    // past the ends it's the distance to the end
+    assert_eq!(distance_to_segment(start, end, vec2(-30.0, 40.0)), 50.0);
+    assert_eq!(distance_to_segment(start, end, vec2(103.0, 4.0)), 5.0);
+    // a segment that's just a point
+    assert_eq!(distance_to_segment(start, start, vec2(3.0, 4.0)), 5.0);
+}
+
+#[test]
+fn line_distance_to_point_is_not_the_projection() {
+    let line = Line::from(vec2(0.0, 0.0), vec2(100.0, 100.0), 1.0);
+    assert!((line.distance_to_point(&vec2(100.0, 0.0)) - 50.0 * 2.0_f32.sqrt()).abs() < 0.001);
+    assert!(line.distance_to_point(&vec2(50.0, 50.0)).abs() < 0.001);
+    assert!((line.distance_to_point(&vec2(-30.0, -40.0)) - 50.0).abs() < 0.001);
+}
+
+#[test]
+fn closest_point_is_clamped_to_the_segment() {
+    let (start, end) = (vec2(10.0, 10.0), vec2(10.0, 50.0));
+    assert!(close(closest_point_on_segment(start, end, vec2(0.0, 30.0)), vec2(10.0, 30.0)));
+    assert!(close(closest_point_on_segment(start, end, vec2(0.0, 80.0)), end));
+    assert!(close(closest_point_on_segment(start, end, vec2(20.0, -5.0)), start));
+}
+
+#[test]
+fn side_is_positive_on_the_left() {
+    let (start, end) = (vec2(0.0, 0.0), vec2(10.0, 0.0));
+    assert!(side(start, end, vec2(5.0, 1.0)) > 0.0);
+    assert!(side(start, end, vec2(5.0, -1.0)) < 0.0);
+    assert_eq!(side(start, end, vec2(20.0, 0.0)), 0.0);
+    assert!(side(end, start, vec2(5.0, 1.0)) < 0.0);
+}
+
+#[test]
+fn rays_cross_segments_in_front_and_behind() {
+    let (start, end) = (vec2(10.0, -10.0), vec2(10.0, 10.0));
+    assert_eq!(ray_segment_intersection(vec2(0.0, 0.0), vec2(1.0, 0.0), start, end), Some(10.0));
+    assert_eq!(ray_segment_intersection(vec2(20.0, 0.0), vec2(1.0, 0.0), start, end), Some(-10.0));
+    assert_eq!(ray_segment_intersection(vec2(0.0, 20.0), vec2(1.0, 0.0), start, end), None);
+    // parallel
+    assert_eq!(ray_segment_intersection(vec2(0.0, 0.0), vec2(0.0, 1.0), start, end), None);
+}
+
+#[test]
+fn segments_cross_only_within_both() {
+    let crossing = segment_intersection(vec2(0.0, 0.0), vec2(10.0, 10.0), vec2(0.0, 10.0), vec2(10.0, 0.0));
+    assert!(close(crossing.unwrap(), vec2(5.0, 5.0)));
+    assert!(segment_intersection(vec2(0.0, 0.0), vec2(4.0, 4.0), vec2(0.0, 10.0), vec2(10.0, 0.0)).is_none());
+    assert!(segment_intersection(vec2(0.0, 0.0), vec2(10.0, 0.0), vec2(0.0, 1.0), vec2(10.0, 1.0)).is_none());
+}
+
+#[test]
+fn dragged_ends_snap_to_other_shapes() {
+    let mut shapes = vec![
+        Shape::Line(Line::from(vec2(0.0, 0.0), vec2(100.0, 0.0), 1.0)),
+        Shape::Line(Line::from(vec2(0.0, 50.0), vec2(100.0, 50.0), 1.0)),
+    ];
+    let mut editor = Editor::default();
+    editor.snap = true;
+
+    // onto the other line's end
+    editor.press(&shapes, vec2(100.0, 50.0));
+    editor.drag(&mut shapes, vec2(104.0, 3.0));
+    let Shape::Line(line) = shapes[1] else { panic!() };
+    assert_eq!(line.end, vec2(100.0, 0.0));
+
+    // onto the middle of the other line
+    editor.drag(&mut shapes, vec2(50.0, 5.0));
+    let Shape::Line(line) = shapes[1] else { panic!() };
+    assert!(close(line.end, vec2(50.0, 0.0)));
+
+    // too far away to stick
+    editor.drag(&mut shapes, vec2(50.0, 20.0));
+    let Shape::Line(line) = shapes[1] else { panic!() };
+    assert_eq!(line.end, vec2(50.0, 20.0));
+}
+
+#[test]
+fn dragged_ends_snap_to_where_sides_cross() {
+    let mut shapes = vec![
+        Shape::Line(Line::from(vec2(0.0, 0.0), vec2(100.0, 100.0), 1.0)),
+        Shape::Line(Line::from(vec2(0.0, 100.0), vec2(100.0, 0.0), 1.0)),
+        Shape::Line(Line::from(vec2(200.0, 0.0), vec2(200.0, 50.0), 1.0)),
+    ];
+    let mut editor = Editor::default();
+    editor.snap = true;
+
+    // closer to a point on one of the lines, but the crossing is near enough
+    editor.press(&shapes, vec2(200.0, 50.0));
+    editor.drag(&mut shapes, vec2(53.0, 50.0));
+    let Shape::Line(line) = shapes[2] else { panic!() };
+    assert!(close(line.end, vec2(50.0, 50.0)));
+}
@@ -0,0 +1,97 @@
+use glam::{vec2, Vec2};
+use ray_tracing::mediums::{Gradient, IndexFunction, Medium, GLASS};
+use ray_tracing::rays::Shape;
+use ray_tracing::scene::{Scene, MAX_GRIN_STEPS};
+
+mod common;
+use common::{blue, direction, trace, without_fresnel};
+
+/// tall enough that rays going along x only leave through the right side
+fn graded_block(gradient: Gradient) -> Medium {
+    let mut medium = Medium::new(vec2(-500.0, -1000.0), vec2(500.0, 1000.0), GLASS, blue());
+    medium.gradient = gradient;
+    medium
+}
+
+#[test]
+fn rays_curve_towards_the_higher_index() {
+    // like hot air over a road, the index goes up with height
+    let medium = graded_block(Gradient::Linear { slope: vec2(0.0, 0.001) });
+    let ray = trace(&without_fresnel(vec![Shape::Medium(medium.clone())]), vec2(-400.0, 0.0), vec2(1.0, 0.0));
+
+    let segments = ray.segments();
+    assert!(segments.len() > 10);
+    // it started inside, so the first step is still straight
+    assert_eq!(segments[0].start, vec2(-400.0, 0.0));
+    // about half the curvature times the distance squared higher up
+    let last = segments.last().unwrap();
+    assert!(last.start.x == 500.0);
+    assert!((last.start.y - 0.5 * 0.001 / 1.5 * 900.0 * 900.0).abs() < 10.0);
+    assert!(direction(last).y > 0.0);
+}
+
+#[test]
+fn steps_follow_on_from_each_other() {
+    let medium = graded_block(Gradient::Linear { slope: vec2(0.0, 0.001) });
+    let ray = trace(&without_fresnel(vec![Shape::Medium(medium.clone())]), vec2(-400.0, 0.0), vec2(1.0, 0.0));
+
+    for pair in ray.segments().windows(2) {
+        assert_eq!(pair[0].end, pair[1].start);
+    }
+}
+
+#[test]
+fn radial_gradient_bends_towards_the_middle() {
+    // the index drops away from the middle like in a GRIN lens
+    let mut medium = Medium::new(vec2(-100.0, -100.0), vec2(100.0, 100.0), GLASS, blue());
+    medium.gradient = Gradient::Radial { change: -0.3, radius: 100.0 };
+    let ray = trace(&without_fresnel(vec![Shape::Medium(medium)]), vec2(-200.0, 50.0), vec2(1.0, 0.0));
+
+    let last = ray.segments().last().unwrap();
+    assert_eq!(last.start.x, 100.0);
+    assert!(last.start.y < 50.0);
+    assert!(direction(last).y < 0.0);
+}
+
+#[test]
+fn no_gradient_goes_straight() {
+    let ray = trace(&without_fresnel(vec![Shape::Medium(graded_block(Gradient::None))]), vec2(-400.0, 0.0), vec2(1.0, 0.0));
+    assert_eq!(ray.segments().len(), 2);
+    assert!((ray.segments()[0].end - vec2(500.0, 0.0)).length() < 0.001);
+}
+
+#[test]
+fn custom_gradients_work_but_are_not_saved() {
+    fn rising(offset: Vec2) -> f32 {
+        offset.y * 0.001
+    }
+    let medium = graded_block(Gradient::Custom(IndexFunction(rising)));
+    assert!((medium.index_at(589.3, vec2(0.0, 100.0)) - 1.6).abs() < 0.0001);
+    let ray = trace(&without_fresnel(vec![Shape::Medium(medium.clone())]), vec2(-400.0, 0.0), vec2(1.0, 0.0));
+    assert!(ray.segments().last().unwrap().end.y > 0.0);
+
+    assert!(Scene::new(vec![Shape::Medium(medium)]).to_ron().is_err());
+}
+
+#[test]
+fn zero_or_negative_steps_still_finish() {
+    for step in [0.0, -1.0] {
+        let mut scene = without_fresnel(vec![Shape::Medium(graded_block(Gradient::Linear { slope: vec2(0.0, 0.001) }))]);
+        scene.grin_step = step;
+        scene.max_length = 100.0;
+        let ray = trace(&scene, vec2(-400.0, 0.0), vec2(1.0, 0.0));
+        // it went the whole way in the shortest steps there are
+        assert_eq!(ray.segments().len(), MAX_GRIN_STEPS);
+        let end = ray.segments().last().unwrap().end;
+        assert!((end.x - -300.0).abs() < 1.0);
+    }
+}
+
+#[test]
+fn running_out_of_length_inside_is_untraced() {
+    let mut scene = without_fresnel(vec![Shape::Medium(graded_block(Gradient::Linear { slope: vec2(0.0, 0.001) }))]);
+    scene.max_length = 100.0;
+    let ray = trace(&scene, vec2(-400.0, 0.0), vec2(1.0, 0.0));
+    assert_eq!(ray.stats().escaped, 0.0);
+    assert!((ray.stats().untraced - 1.0).abs() < 0.001);
+}
@@ -0,0 +1,51 @@
+use glam::vec2;
+use ray_tracing::circles::Circle;
+use ray_tracing::history::History;
+use ray_tracing::rays::Shape;
+use ray_tracing::scene::Scene;
+
+fn circle(x: f32) -> Shape {
+    Shape::Circle(Circle::from(vec2(x, 0.0), 10.0))
+}
+
+#[test]
+fn undo_and_redo_a_change() {
+    let mut scene = Scene::new(vec![circle(0.0)]);
+    let mut history = History::new(&scene);
+
+    scene.shapes.push(circle(50.0));
+    history.update(&scene, false);
+    history.undo(&mut scene);
+    assert_eq!(scene.shapes.len(), 1);
+
+    history.redo(&mut scene);
+    assert_eq!(scene.shapes.len(), 2);
+    assert!(!history.can_redo());
+}
+
+#[test]
+fn a_drag_is_one_step() {
+    let mut scene = Scene::new(vec![circle(0.0)]);
+    let mut history = History::new(&scene);
+
+    for x in 1..=10 {
+        scene.shapes[0] = circle(x as f32);
+        history.update(&scene, true);
+    }
+    history.update(&scene, false);
+    history.undo(&mut scene);
+
+    assert_eq!(scene.shapes[0], circle(0.0));
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn aiming_the_light_is_not_an_edit() {
+    let mut scene = Scene::new(vec![circle(0.0)]);
+    let mut history = History::new(&scene);
+
+    scene.light.direction = vec2(0.0, 1.0);
+    history.update(&scene, false);
+
+    assert!(!history.can_undo());
+}
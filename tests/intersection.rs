@@ -0,0 +1,99 @@
+use glam::{vec2, Vec2};
+use ray_tracing::circles::Circle;
+use ray_tracing::lines::Line;
+use ray_tracing::mediums::{Medium, GLASS};
+use ray_tracing::rays::Shape_Util;
+
+mod common;
+use common::{blue, close};
+
+fn square() -> Medium {
+    Medium::new(vec2(-10.0, -10.0), vec2(10.0, 10.0), GLASS, blue())
+}
+
+#[test]
+fn line_is_hit_head_on_and_missed_past_its_ends() {
+    let line = Line::from(vec2(0.0, -10.0), vec2(0.0, 10.0), 1.0);
+    let hit = line.ray_intersect(vec2(-10.0, 0.0), vec2(1.0, 0.0)).unwrap();
+    assert!((hit.distance - 10.0).abs() < 0.001);
+    assert!(close(hit.point, Vec2::ZERO));
+    assert!(close(hit.normal, vec2(-1.0, 0.0)));
+
+    assert!(line.ray_intersect(vec2(-10.0, 11.0), vec2(1.0, 0.0)).is_none());
+    assert!(line.ray_intersect(vec2(10.0, 0.0), vec2(1.0, 0.0)).is_none());
+    // parallel to it
+    assert!(line.ray_intersect(vec2(-10.0, 0.0), vec2(0.0, 1.0)).is_none());
+}
+
+#[test]
+fn line_is_grazed_at_its_end() {
+    let line = Line::from(vec2(0.0, -10.0), vec2(0.0, 10.0), 1.0);
+    let hit = line.ray_intersect(vec2(-10.0, 10.0), vec2(1.0, 0.0)).unwrap();
+    assert!(close(hit.point, vec2(0.0, 10.0)));
+}
+
+#[test]
+fn ray_starting_on_a_line_leaves_it() {
+    let line = Line::from(vec2(0.0, -10.0), vec2(0.0, 10.0), 1.0);
+    assert!(line.ray_intersect(Vec2::ZERO, vec2(1.0, 0.0)).is_none());
+    assert!(line.ray_intersect(Vec2::ZERO, vec2(-1.0, 1.0).normalize()).is_none());
+}
+
+#[test]
+fn circle_is_hit_on_the_near_side() {
+    let circle = Circle::from(Vec2::ZERO, 10.0);
+    let hit = circle.ray_intersect(vec2(-30.0, 0.0), vec2(1.0, 0.0)).unwrap();
+    assert!((hit.distance - 20.0).abs() < 0.001);
+    assert!(close(hit.point, vec2(-10.0, 0.0)));
+    assert!(close(hit.normal, vec2(-1.0, 0.0)));
+    assert!(circle.ray_intersect(vec2(-30.0, 0.0), vec2(-1.0, 0.0)).is_none());
+}
+
+#[test]
+fn circle_is_grazed_at_the_tangent() {
+    let circle = Circle::from(Vec2::ZERO, 10.0);
+    let hit = circle.ray_intersect(vec2(-20.0, 10.0), vec2(1.0, 0.0)).unwrap();
+    assert!(close(hit.point, vec2(0.0, 10.0)));
+    assert!(close(hit.normal, vec2(0.0, 1.0)));
+    assert!(circle.ray_intersect(vec2(-20.0, 10.01), vec2(1.0, 0.0)).is_none());
+}
+
+#[test]
+fn ray_starting_on_a_circle_hits_the_far_side_or_nothing() {
+    let circle = Circle::from(Vec2::ZERO, 10.0);
+    let inwards = circle.ray_intersect(vec2(10.0, 0.0), vec2(-1.0, 0.0)).unwrap();
+    assert!(close(inwards.point, vec2(-10.0, 0.0)));
+    assert!(circle.ray_intersect(vec2(10.0, 0.0), vec2(1.0, 0.0)).is_none());
+}
+
+#[test]
+fn box_is_hit_from_outside_and_inside() {
+    let square = square();
+    let outside = square.ray_intersect(vec2(-30.0, 0.0), vec2(1.0, 0.0)).unwrap();
+    assert!((outside.distance - 20.0).abs() < 0.001);
+    assert!(close(outside.point, vec2(-10.0, 0.0)));
+    assert!(close(outside.normal, vec2(-1.0, 0.0)));
+
+    let inside = square.ray_intersect(Vec2::ZERO, vec2(1.0, 0.0)).unwrap();
+    assert!(close(inside.point, vec2(10.0, 0.0)));
+    assert!(close(inside.normal, vec2(1.0, 0.0)));
+}
+
+#[test]
+fn box_is_grazed_along_a_side_and_at_a_corner() {
+    let square = square();
+    // along the top, it first touches the top left corner
+    let along = square.ray_intersect(vec2(-30.0, 10.0), vec2(1.0, 0.0)).unwrap();
+    assert!(close(along.point, vec2(-10.0, 10.0)));
+    let corner = square.ray_intersect(vec2(-20.0, 0.0), vec2(1.0, 1.0).normalize()).unwrap();
+    assert!(close(corner.point, vec2(-10.0, 10.0)));
+    assert!(square.ray_intersect(vec2(-30.0, 10.01), vec2(1.0, 0.0)).is_none());
+}
+
+#[test]
+fn ray_starting_on_a_box_crosses_to_the_other_side() {
+    let square = square();
+    let hit = square.ray_intersect(vec2(-10.0, 0.0), vec2(1.0, 0.0)).unwrap();
+    assert!((hit.distance - 20.0).abs() < 0.001);
+    assert!(close(hit.point, vec2(10.0, 0.0)));
+}
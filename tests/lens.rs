@@ -0,0 +1,82 @@
+use std::f32::consts::PI;
+
+use glam::{vec2, Vec2};
+use ray_tracing::mediums::{Medium, Side, GLASS, WATER};
+use ray_tracing::rays::{Shape, Shape_Util};
+
+mod common;
+use common::{blue, direction, trace, without_fresnel};
+
+#[test]
+fn disk_has_round_normals_and_area() {
+    let disk = Medium::disk(vec2(10.0, 20.0), 50.0, WATER, blue());
+    assert!((disk.signed_area() - PI * 50.0 * 50.0).abs() < 1.0);
+    assert!(disk.intersect(&vec2(-35.0, 20.0)));
+    assert!(disk.intersect(&vec2(50.0, 40.0)));
+    assert!(!disk.intersect(&vec2(50.0, 60.0)));
+
+    let hit = disk.ray_intersect(vec2(-100.0, 50.0), vec2(1.0, 0.0)).unwrap();
+    assert!((hit.point.distance(vec2(10.0, 20.0)) - 50.0).abs() < 0.01);
+    assert!((hit.normal - (hit.point - vec2(10.0, 20.0)) / 50.0).length() < 0.001);
+}
+
+#[test]
+fn ray_through_the_middle_of_a_disk_goes_straight() {
+    let disk = Medium::disk(Vec2::ZERO, 50.0, WATER, blue());
+    let ray = trace(&without_fresnel(vec![Shape::Medium(disk)]), vec2(-100.0, 0.0), vec2(1.0, 0.0));
+    let last = ray.segments().last().unwrap();
+    assert!((last.start - vec2(50.0, 0.0)).length() < 0.01);
+    assert!((direction(last) - vec2(1.0, 0.0)).length() < 0.001);
+}
+
+#[test]
+fn clockwise_sides_bulge_out_too() {
+    let square = vec![vec2(-50.0, -50.0), vec2(-50.0, 50.0), vec2(50.0, 50.0), vec2(50.0, -50.0)];
+    let mut medium = Medium::polygon(square, GLASS, blue());
+    medium.sides = vec![Side::Arc { radius: 50.0 }; 4];
+    // the square and four half disks
+    assert_eq!(medium.signed_area().round(), -25708.0);
+    assert!(medium.intersect(&vec2(0.0, -90.0)));
+
+    let hit = medium.ray_intersect(vec2(0.0, -200.0), vec2(0.0, 1.0)).unwrap();
+    assert!((hit.point - vec2(0.0, -100.0)).length() < 0.01);
+}
+
+#[test]
+fn biconvex_lens_focuses_at_its_back_focal_length() {
+    let (radius, thickness, index) = (200.0, 20.0, GLASS);
+    let lens = Medium::biconvex(Vec2::ZERO, radius, thickness, 80.0, index, blue());
+    // lensmaker's equation for a thick lens
+    let power = (index - 1.0) * (2.0 / radius - (index - 1.0) * thickness / (index * radius * radius));
+    let back_focus = thickness / 2.0 + (1.0 - (index - 1.0) * thickness / (index * radius)) / power;
+
+    let ray = trace(&without_fresnel(vec![Shape::Medium(lens)]), vec2(-100.0, 2.0), vec2(1.0, 0.0));
+    let last = ray.segments().last().unwrap();
+    let exit = direction(last);
+    assert!(exit.y < 0.0);
+    let crossing = last.start.x - last.start.y / exit.y * exit.x;
+    assert!((crossing - back_focus).abs() < back_focus * 0.01, "{} {}", crossing, back_focus);
+}
+
+#[test]
+fn biconcave_lens_spreads_light() {
+    let lens = Medium::biconcave(Vec2::ZERO, 200.0, 10.0, 80.0, GLASS, blue());
+    assert!(lens.intersect(&vec2(0.0, 0.0)));
+    // the corners stick out further than the middle
+    assert!(lens.intersect(&vec2(-8.0, 39.0)));
+    assert!(!lens.intersect(&vec2(-8.0, 0.0)));
+
+    let ray = trace(&without_fresnel(vec![Shape::Medium(lens)]), vec2(-100.0, 10.0), vec2(1.0, 0.0));
+    assert!(direction(ray.segments().last().unwrap()).y > 0.0);
+}
+
+#[test]
+fn plano_convex_lens_is_flat_on_the_back() {
+    let lens = Medium::plano_convex(Vec2::ZERO, 100.0, 20.0, 60.0, GLASS, blue());
+    assert_eq!(lens.side(0), Side::Arc { radius: 100.0 });
+    assert_eq!(lens.side(2), Side::Straight);
+    assert!(lens.points.iter().all(|point| point.x >= -10.001));
+    let hit = lens.ray_intersect(vec2(100.0, 5.0), vec2(-1.0, 0.0)).unwrap();
+    assert!((hit.point - vec2(10.0, 5.0)).length() < 0.001);
+    assert!((hit.normal - vec2(1.0, 0.0)).length() < 0.001);
+}
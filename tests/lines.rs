@@ -0,0 +1,90 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use glam::{vec2, Vec2};
+use ray_tracing::lines::Line;
+use ray_tracing::rays::{Ray, Shape, Shape_Util};
+use ray_tracing::scene::Scene;
+
+mod common;
+use common::{close, trace};
+
+/// which way a ray goes after bouncing off the line once
+fn bounce(line: Line, start: Vec2, direction: Vec2) -> Vec2 {
+    let shapes = vec![Shape::Line(line)];
+    let hits = Ray::ray_trace(&shapes, start, direction, 1000.0);
+    let (_, hit, _) = hits[0];
+    Ray::bounce_angle(&shapes[0], &hit, start, 1)[0].0
+}
+
+#[test]
+fn horizontal_line_flips_the_vertical_part() {
+    let line = Line::from(vec2(-100.0, 0.0), vec2(100.0, 0.0), 1.0);
+    assert!(close(bounce(line, vec2(-50.0, 50.0), vec2(1.0, -1.0).normalize()), vec2(1.0, 1.0).normalize()));
+    assert!(close(bounce(line, vec2(0.0, -50.0), vec2(0.0, 1.0)), vec2(0.0, -1.0)));
+}
+
+#[test]
+fn vertical_line_flips_the_horizontal_part() {
+    let line = Line::from(vec2(0.0, -100.0), vec2(0.0, 100.0), 1.0);
+    assert!(close(bounce(line, vec2(-50.0, 0.0), vec2(1.0, 0.0)), vec2(-1.0, 0.0)));
+    assert!(close(bounce(line, vec2(50.0, 20.0), vec2(-1.0, 0.5).normalize()), vec2(1.0, 0.5).normalize()));
+}
+
+#[test]
+fn diagonal_line_turns_rays_a_quarter() {
+    let line = Line::from(vec2(-100.0, -100.0), vec2(100.0, 100.0), 1.0);
+    assert!(close(bounce(line, vec2(-50.0, 0.0), vec2(1.0, 0.0)), vec2(0.0, 1.0)));
+    assert!(close(bounce(line, vec2(0.0, -50.0), vec2(0.0, 1.0)), vec2(1.0, 0.0)));
+}
+
+#[test]
+fn every_orientation_keeps_the_angle_of_incidence() {
+    for step in 0..72 {
+        let angle = step as f32 * 5.0_f32.to_radians();
+        let along = vec2(angle.cos(), angle.sin());
+        let normal = along.perp();
+        // both ways round and from both sides
+        for line in [Line::from(-along * 100.0, along * 100.0, 1.0), Line::from(along * 100.0, -along * 100.0, 1.0)] {
+            for side in [1.0, -1.0] {
+                let direction = (-normal * side + along * 0.4).normalize();
+                let bounced = bounce(line, normal * side * 50.0, direction);
+                let expected = direction - 2.0 * direction.dot(normal) * normal;
+                assert!(close(bounced, expected), "{} degrees, side {}: {:?} instead of {:?}", step * 5, side, bounced, expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn hit_normal_faces_the_ray_on_both_sides() {
+    let line = Line::from(vec2(0.0, -10.0), vec2(0.0, 10.0), 1.0);
+    assert!(close(line.normal(), vec2(-1.0, 0.0)));
+    let front = line.ray_intersect(vec2(-5.0, 0.0), vec2(1.0, 0.0)).unwrap();
+    assert!(close(front.normal, vec2(-1.0, 0.0)));
+    let back = line.ray_intersect(vec2(5.0, 0.0), vec2(-1.0, 0.0)).unwrap();
+    assert!(close(back.normal, vec2(1.0, 0.0)));
+}
+
+#[test]
+fn turned_line_reflects_in_the_world() {
+    let mut line = Line::from(vec2(-100.0, 0.0), vec2(100.0, 0.0), 1.0);
+    line.transform.rotation = FRAC_PI_2;
+    assert!(close(bounce(line, vec2(-50.0, 0.0), vec2(1.0, 0.0)), vec2(-1.0, 0.0)));
+    line.transform.rotation = PI / 4.0;
+    assert!(close(bounce(line, vec2(-50.0, 0.0), vec2(1.0, 0.0)), vec2(0.0, 1.0)));
+}
+
+#[test]
+fn scene_ray_bounces_between_parallel_mirrors() {
+    let shapes = vec![
+        Shape::Line(Line::from(vec2(-100.0, 0.0), vec2(100.0, 0.0), 1.0)),
+        Shape::Line(Line::from(vec2(-100.0, 100.0), vec2(100.0, 100.0), 1.0)),
+    ];
+    let mut scene = Scene::new(shapes);
+    scene.bounces = 4;
+    let ray = trace(&scene, vec2(0.0, 50.0), vec2(0.0, 1.0));
+    let ends: Vec<Vec2> = ray.segments().iter().map(|segment| segment.end).collect();
+    assert!(close(ends[0], vec2(0.0, 100.0)));
+    assert!(close(ends[1], vec2(0.0, 0.0)));
+    assert!(close(ends[2], vec2(0.0, 100.0)));
+}
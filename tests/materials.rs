@@ -0,0 +1,82 @@
+use glam::{vec2, Vec2};
+use ray_tracing::lines::Line;
+use ray_tracing::materials::Material;
+use ray_tracing::mediums::{Medium, GLASS};
+use ray_tracing::rays::{Ray, Shape};
+use ray_tracing::scene::Scene;
+
+mod common;
+use common::{blue, close, direction, trace};
+
+/// a vertical wall at x = 100
+fn wall(material: Material) -> Shape {
+    let mut line = Line::from(vec2(100.0, -50.0), vec2(100.0, 50.0), 1.0);
+    line.material = material;
+    Shape::Line(line)
+}
+
+/// where a ray along x from the origin goes after hitting the wall
+fn bounce(wall: &Shape) -> Vec<(Vec2, f32)> {
+    let hit = wall.ray_intersect(Vec2::ZERO, vec2(1.0, 0.0)).unwrap();
+    Ray::bounce_angle(wall, &hit, Vec2::ZERO, 8)
+}
+
+#[test]
+fn diffuse_fans_out_back_towards_the_ray() {
+    let shape = wall(Material::Diffuse);
+    let bounced = bounce(&shape);
+    assert_eq!(bounced.len(), 8);
+    assert!((bounced.iter().map(|(_, share)| share).sum::<f32>() - 1.0).abs() < 0.001);
+    assert!(bounced.iter().all(|(direction, _)| direction.x < 0.0));
+    // as many going up as down
+    let sideways: f32 = bounced.iter().map(|(direction, _)| direction.y).sum();
+    assert!(sideways.abs() < 0.001);
+}
+
+#[test]
+fn partial_mirror_splits_the_power() {
+    let shape = wall(Material::PartialMirror { reflectivity: 0.3 });
+    let bounced = bounce(&shape);
+    assert!(close(bounced[0].0, vec2(-1.0, 0.0)));
+    assert!((bounced[0].1 - 0.3).abs() < 0.001);
+    assert!(close(bounced[1].0, vec2(1.0, 0.0)));
+    assert!((bounced[1].1 - 0.7).abs() < 0.001);
+
+    let mut scene = Scene::new(vec![shape]);
+    scene.bounces = 2;
+    let ray = trace(&scene, Vec2::ZERO, vec2(1.0, 0.0));
+    let powers: Vec<f32> = ray.segments().iter().skip(1).map(|segment| segment.power).collect();
+    assert!(powers.iter().any(|power| (power - 0.3).abs() < 0.001));
+    assert!(powers.iter().any(|power| (power - 0.7).abs() < 0.001));
+}
+
+#[test]
+fn transparent_lets_the_ray_through() {
+    let shape = wall(Material::Transparent);
+    let bounced = bounce(&shape);
+    assert_eq!(bounced.len(), 1);
+    assert!(close(bounced[0].0, vec2(1.0, 0.0)));
+
+    let ray = trace(&Scene::new(vec![shape]), Vec2::ZERO, vec2(1.0, 0.0));
+    assert!(ray.segments().iter().all(|segment| segment.end.x >= segment.start.x));
+    assert!((ray.stats().escaped - 1.0).abs() < 0.001);
+}
+
+#[test]
+fn absorber_stops_the_ray() {
+    let shape = wall(Material::Absorber);
+    assert!(bounce(&shape).is_empty());
+
+    let ray = trace(&Scene::new(vec![shape]), Vec2::ZERO, vec2(1.0, 0.0));
+    assert_eq!(ray.segments().len(), 1);
+    assert!((ray.stats().absorbed - 1.0).abs() < 0.001);
+}
+
+#[test]
+fn transparent_medium_does_not_bend_the_ray() {
+    let mut medium = Medium::new(vec2(50.0, -100.0), vec2(150.0, 100.0), GLASS, blue());
+    medium.material = Material::Transparent;
+    let start = vec2(1.0, 0.2).normalize();
+    let ray = trace(&Scene::new(vec![Shape::Medium(medium)]), Vec2::ZERO, start);
+    assert!(ray.segments().iter().all(|segment| close(direction(segment), start)));
+}
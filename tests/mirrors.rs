@@ -0,0 +1,84 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+use glam::{vec2, Vec2};
+use ray_tracing::mirrors::Mirror;
+use ray_tracing::rays::{Shape, Shape_Util};
+use ray_tracing::scene::Scene;
+
+mod common;
+use common::trace;
+
+/// where the ray first hits and which way it goes after bouncing
+fn bounce(mirror: Mirror, start: Vec2, direction: Vec2) -> (Vec2, Vec2) {
+    let mut scene = Scene::new(vec![Shape::Mirror(mirror)]);
+    scene.bounces = 2;
+    let ray = trace(&scene, start, direction.normalize());
+    let segments = ray.segments();
+    assert!(segments.len() >= 2);
+    (segments[1].start, common::direction(&segments[1]))
+}
+
+/// how far a point is from the line a ray goes along
+fn miss(start: Vec2, direction: Vec2, point: Vec2) -> f32 {
+    direction.perp_dot(point - start).abs()
+}
+
+#[test]
+fn parabola_focuses_parallel_rays() {
+    let mirror = Mirror::parabola(vec2(20.0, 10.0), 50.0, 200.0);
+    let focus = mirror.foci()[0];
+    assert_eq!(focus, vec2(70.0, 10.0));
+    for height in [-90.0, -40.0, 5.0, 60.0] {
+        let (start, direction) = bounce(mirror, vec2(300.0, 10.0 + height), vec2(-1.0, 0.0));
+        assert!(miss(start, direction, focus) < 0.01, "{} misses by {}", height, miss(start, direction, focus));
+    }
+}
+
+#[test]
+fn turned_parabola_still_focuses() {
+    let mut mirror = Mirror::parabola(Vec2::ZERO, 80.0, 300.0);
+    mirror.transform.rotation = FRAC_PI_2;
+    let focus = mirror.foci()[0];
+    assert!((focus - vec2(0.0, 80.0)).length() < 0.001);
+    let (start, direction) = bounce(mirror, vec2(100.0, 500.0), vec2(0.0, -1.0));
+    assert!(miss(start, direction, focus) < 0.01);
+}
+
+#[test]
+fn ellipse_sends_light_from_one_focus_to_the_other() {
+    let mirror = Mirror::ellipse(Vec2::ZERO, vec2(200.0, 120.0), 0.0, 2.0 * PI);
+    let foci = mirror.foci();
+    assert_eq!(foci, vec![vec2(-160.0, 0.0), vec2(160.0, 0.0)]);
+    for angle in [0.3_f32, 1.2, 2.5, 4.0] {
+        let (start, direction) = bounce(mirror, foci[0], vec2(angle.cos(), angle.sin()));
+        assert!(miss(start, direction, foci[1]) < 0.05);
+    }
+}
+
+#[test]
+fn arc_reflects_rays_from_its_center_back() {
+    let mirror = Mirror::arc(vec2(10.0, 10.0), 100.0, -FRAC_PI_4, FRAC_PI_4);
+    let direction = vec2(1.0, 0.3).normalize();
+    let (start, bounced) = bounce(mirror, vec2(10.0, 10.0), direction);
+    assert!((start.distance(vec2(10.0, 10.0)) - 100.0).abs() < 0.01);
+    assert!((bounced + direction).length() < 0.001);
+    assert!((mirror.normal(start).abs() - direction.abs()).length() < 0.001);
+}
+
+#[test]
+fn arc_misses_outside_its_ends() {
+    let mirror = Mirror::arc(Vec2::ZERO, 100.0, -FRAC_PI_4, FRAC_PI_4);
+    assert!(mirror.ray_intersect(Vec2::ZERO, vec2(0.0, 1.0)).is_none());
+    assert!(mirror.ray_intersect(Vec2::ZERO, vec2(-1.0, 0.0)).is_none());
+    // from outside it's hit on the back
+    let hit = mirror.ray_intersect(vec2(300.0, 0.0), vec2(-1.0, 0.0)).unwrap();
+    assert!((hit.point - vec2(100.0, 0.0)).length() < 0.001);
+    assert!((hit.normal - vec2(1.0, 0.0)).length() < 0.001);
+}
+
+#[test]
+fn mirrors_are_saved_in_scene_files() {
+    let scene = Scene::new(vec![Shape::Mirror(Mirror::parabola(vec2(1.0, 2.0), 30.0, 90.0))]);
+    let text = scene.to_ron().unwrap();
+    assert_eq!(Scene::from_ron(&text).unwrap(), scene);
+}
@@ -0,0 +1,77 @@
+use glam::{vec2, Vec2};
+use ray_tracing::mediums::{Medium, GLASS};
+use ray_tracing::rays::{Shape, Shape_Util};
+use ray_tracing::scene::Scene;
+
+mod common;
+use common::{blue, close, direction, trace, without_fresnel};
+
+fn glass(points: Vec<Vec2>) -> Medium {
+    Medium::polygon(points, GLASS, blue())
+}
+
+#[test]
+fn prism_bends_light_towards_its_base() {
+    let prism = vec![vec2(-100.0, 0.0), vec2(100.0, 0.0), vec2(0.0, 173.0)];
+    let ray = trace(&without_fresnel(vec![Shape::Medium(glass(prism))]), vec2(-200.0, 80.0), vec2(1.0, 0.0));
+    let exit = direction(ray.segments().last().unwrap());
+    assert!(exit.x > 0.0);
+    assert!(exit.y < -0.1);
+}
+
+#[test]
+fn winding_order_does_not_matter() {
+    let counterclockwise = vec![vec2(-100.0, 0.0), vec2(100.0, 0.0), vec2(0.0, 173.0)];
+    let clockwise: Vec<Vec2> = counterclockwise.iter().rev().copied().collect();
+    assert!(glass(counterclockwise.clone()).signed_area() > 0.0);
+    assert!(glass(clockwise.clone()).signed_area() < 0.0);
+
+    let start = vec2(-200.0, 80.0);
+    let a = trace(&without_fresnel(vec![Shape::Medium(glass(counterclockwise))]), start, vec2(1.0, 0.0));
+    let b = trace(&without_fresnel(vec![Shape::Medium(glass(clockwise))]), start, vec2(1.0, 0.0));
+    assert!(close(direction(a.segments().last().unwrap()), direction(b.segments().last().unwrap())));
+}
+
+#[test]
+fn rotated_slab_leaves_parallel() {
+    let slab = vec![vec2(0.0, 0.0), vec2(100.0, 100.0), vec2(50.0, 150.0), vec2(-50.0, 50.0)];
+    let start = vec2(1.0, 0.2).normalize();
+    let ray = trace(&without_fresnel(vec![Shape::Medium(glass(slab))]), vec2(-200.0, 60.0), start);
+    assert!(close(direction(ray.segments().last().unwrap()), start));
+}
+
+#[test]
+fn normals_point_out_of_every_side() {
+    let medium = glass(vec![vec2(0.0, 0.0), vec2(100.0, 0.0), vec2(0.0, 100.0)]);
+    let hit = medium.ray_intersect(vec2(-50.0, 50.0), vec2(1.0, 0.0)).unwrap();
+    assert!(close(hit.normal, vec2(-1.0, 0.0)));
+    // from inside, the slanted side is hit on the way out
+    let hit = medium.ray_intersect(vec2(10.0, 10.0), vec2(1.0, 0.0)).unwrap();
+    assert!(close(hit.point, vec2(90.0, 10.0)));
+    assert!(close(hit.normal, vec2(1.0, 1.0).normalize()));
+}
+
+#[test]
+fn concave_polygons_know_what_is_inside() {
+    // a U shape
+    let medium = glass(vec![
+        vec2(0.0, 0.0),
+        vec2(100.0, 0.0),
+        vec2(100.0, 100.0),
+        vec2(70.0, 100.0),
+        vec2(70.0, 30.0),
+        vec2(30.0, 30.0),
+        vec2(30.0, 100.0),
+        vec2(0.0, 100.0),
+    ]);
+    assert!(medium.intersect(&vec2(15.0, 80.0)));
+    assert!(!medium.intersect(&vec2(50.0, 80.0)));
+    assert!(medium.intersect(&vec2(50.0, 10.0)));
+}
+
+#[test]
+fn boxes_can_still_be_written_with_min_and_max() {
+    let text = "(shapes: [Medium((min: (0.0, 0.0), max: (10.0, 20.0), refractive_index: 1.5, color: (red: 0.0, green: 0.0, blue: 1.0, alpha: 0.5)))])";
+    let scene = Scene::from_ron(text).unwrap();
+    assert_eq!(scene.shapes[0], Shape::Medium(glass(vec![vec2(0.0, 0.0), vec2(10.0, 0.0), vec2(10.0, 20.0), vec2(0.0, 20.0)])));
+}
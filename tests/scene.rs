@@ -0,0 +1,46 @@
+use glam::vec2;
+use ray_tracing::circles::Circle;
+use ray_tracing::rays::Shape;
+use ray_tracing::scene::Scene;
+
+#[test]
+fn scene_round_trips_through_ron() {
+    let mut scene = Scene::new(vec![Shape::Circle(Circle::from(vec2(10.0, 20.0), 5.0))]);
+    scene.bounces = 3;
+    let text = scene.to_ron().unwrap();
+
+    let loaded = Scene::from_ron(&text).unwrap();
+    assert_eq!(loaded.bounces, 3);
+    assert_eq!(loaded.to_ron().unwrap(), text);
+}
+
+#[test]
+fn missing_fields_use_defaults() {
+    let scene = Scene::from_ron("(shapes: [Circle((pos: (0.0, 0.0), radius: 5.0))])").unwrap();
+    assert_eq!(scene.shapes.len(), 1);
+    assert_eq!(scene.bounces, Scene::default().bounces);
+}
+
+#[test]
+fn default_scene_file_loads() {
+    let scene = Scene::load(concat!(env!("CARGO_MANIFEST_DIR"), "/scenes/default.ron")).unwrap();
+    assert_eq!(scene.shapes.len(), 5);
+}
+
+#[test]
+fn mirage_scene_file_loads() {
+    let scene = Scene::load(concat!(env!("CARGO_MANIFEST_DIR"), "/scenes/mirage.ron")).unwrap();
+    assert!(scene.shapes.iter().any(|shape| matches!(shape, Shape::Medium(medium) if medium.is_graded())));
+}
+
+#[test]
+fn prism_scene_file_loads() {
+    let scene = Scene::load(concat!(env!("CARGO_MANIFEST_DIR"), "/scenes/prism.ron")).unwrap();
+    assert!(scene.shapes.iter().any(|shape| matches!(shape, Shape::Medium(medium) if medium.points.len() == 3)));
+}
+
+#[test]
+fn gallery_scene_file_loads() {
+    let scene = Scene::load(concat!(env!("CARGO_MANIFEST_DIR"), "/scenes/gallery.ron")).unwrap();
+    assert!(scene.shapes.iter().any(|shape| matches!(shape, Shape::Mirror(_))));
+}
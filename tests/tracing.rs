@@ -0,0 +1,225 @@
+use glam::vec2;
+use ray_tracing::lines::Line;
+use ray_tracing::materials::Material;
+use ray_tracing::mediums::{Absorption, Medium, AIR, DIAMOND, GLASS, WATER};
+use ray_tracing::rays::{Ray, Shape};
+use ray_tracing::scene::Scene;
+
+mod common;
+use common::{blue, close, direction, trace};
+
+fn glass_slab() -> Shape {
+    Shape::Medium(Medium::new(
+        vec2(-500.0, -100.0),
+        vec2(500.0, 100.0),
+        GLASS,
+        blue(),
+    ))
+}
+
+#[test]
+fn mirror_reflects_ray_back() {
+    let mut scene = Scene::new(vec![Shape::Line(Line::from(vec2(100.0, -50.0), vec2(100.0, 50.0), 1.0))]);
+    scene.bounces = 2;
+    let ray = trace(&scene, vec2(0.0, 0.0), vec2(1.0, 0.0));
+
+    let segments = ray.segments();
+    assert!(close(segments[0].end, vec2(100.0, 0.0)));
+    assert!(segments[1].end.x < 0.0);
+}
+
+#[test]
+fn slab_refracts_and_leaves_parallel() {
+    let mut scene = Scene::new(vec![glass_slab()]);
+    scene.bounces = 3;
+    scene.fresnel = false;
+    let start = vec2(1.0, 1.0).normalize();
+    let ray = trace(&scene, vec2(0.0, -200.0), start);
+
+    let segments = ray.segments();
+    assert_eq!(segments.len(), 3);
+    assert!(close(segments[0].end, vec2(100.0, -100.0)));
+    // bent towards the normal inside the glass
+    assert!(direction(&segments[1]).y > start.y);
+    assert!(close(direction(&segments[2]), start));
+}
+
+#[test]
+fn fresnel_splits_power_at_a_border() {
+    let mut scene = Scene::new(vec![glass_slab()]);
+    scene.bounces = 2;
+    let ray = trace(&scene, vec2(0.0, -200.0), vec2(0.0, 1.0));
+
+    // straight on, glass reflects 4%
+    let segments = ray.segments();
+    assert_eq!(segments.len(), 3);
+    let transmitted = segments[1];
+    let reflected = segments[2];
+    assert!(direction(&transmitted).y > 0.0);
+    assert!(direction(&reflected).y < 0.0);
+    assert!((reflected.power - 0.04).abs() < 0.001);
+    assert!((transmitted.power + reflected.power - 1.0).abs() < 0.001);
+}
+
+#[test]
+fn fresnel_reflects_everything_past_the_critical_angle() {
+    let direction_in = vec2(1.0, 0.2).normalize();
+    let normal = vec2(0.0, -1.0);
+    assert_eq!(Medium::fresnel_reflectance(GLASS, 1.0, direction_in, normal), 1.0);
+    assert!(Medium::fresnel_reflectance(1.0, GLASS, vec2(0.0, 1.0), normal) < 0.05);
+}
+
+#[test]
+fn weak_branches_are_cut_off() {
+    let mut scene = Scene::new(vec![glass_slab()]);
+    scene.bounces = 10;
+    scene.min_power = 0.05;
+    let ray = trace(&scene, vec2(0.0, -200.0), vec2(0.0, 1.0));
+
+    // the 4% reflections are dropped, only the straight path is left
+    assert_eq!(ray.segments().len(), 3);
+    assert!(ray.segments().iter().all(|segment| segment.power >= 0.05));
+}
+
+#[test]
+fn absorber_stops_the_ray() {
+    let mut line = Line::from(vec2(100.0, -50.0), vec2(100.0, 50.0), 1.0);
+    line.material = Material::Absorber;
+    let scene = Scene::new(vec![Shape::Line(line)]);
+    let ray = trace(&scene, vec2(0.0, 0.0), vec2(1.0, 0.0));
+
+    assert_eq!(ray.segments().len(), 1);
+    assert!(close(ray.segments()[0].end, vec2(100.0, 0.0)));
+}
+
+#[test]
+fn mirror_medium_reflects_instead_of_refracting() {
+    let mut medium = Medium::new(vec2(100.0, -50.0), vec2(200.0, 50.0), GLASS, blue());
+    medium.material = Material::Mirror;
+    let mut scene = Scene::new(vec![Shape::Medium(medium)]);
+    scene.bounces = 2;
+    let ray = trace(&scene, vec2(0.0, 0.0), vec2(1.0, 0.0));
+
+    let segments = ray.segments();
+    assert!(close(segments[0].end, vec2(100.0, 0.0)));
+    assert!(close(direction(&segments[1]), vec2(-1.0, 0.0)));
+}
+
+#[test]
+fn escaping_ray_stops_early() {
+    let mut scene = Scene::new(vec![Shape::Line(Line::from(vec2(100.0, -50.0), vec2(100.0, 50.0), 1.0))]);
+    scene.bounces = 10;
+    scene.max_length = 500.0;
+    let ray = trace(&scene, vec2(0.0, 0.0), vec2(1.0, 0.0));
+
+    // to the mirror, then out of the scene
+    let segments = ray.segments();
+    assert_eq!(segments.len(), 2);
+    assert!(((segments[1].end - segments[1].start).length() - 500.0).abs() < 0.001);
+}
+
+#[test]
+fn power_is_accounted_for() {
+    let mut absorber = Line::from(vec2(-100.0, 150.0), vec2(100.0, 150.0), 1.0);
+    absorber.material = Material::Absorber;
+    let scene = Scene::new(vec![glass_slab(), Shape::Line(absorber)]);
+    let mut ray = Ray::new(vec2(0.0, -200.0), vec2(0.0, 1.0), vec2(0.0, 1.0));
+    ray.power = 2.0;
+    scene.trace(&mut ray);
+
+    let stats = ray.stats();
+    assert!((stats.total() - 2.0).abs() < 0.001);
+    // two glass surfaces each reflect 4% back out of the scene
+    assert!(stats.absorbed > 1.8 && stats.absorbed < 1.9);
+    assert!(stats.escaped > 0.1);
+    assert_eq!(ray.segments()[0].power, 2.0);
+}
+
+#[test]
+fn light_gives_its_power_to_every_ray() {
+    let mut scene = Scene::new(vec![]);
+    scene.light.power = 0.5;
+    let rays = scene.trace_light();
+
+    assert!(rays.iter().all(|ray| ray.power == 0.5));
+    let stats = Scene::stats(&rays);
+    assert!((stats.escaped - 0.5 * rays.len() as f32).abs() < 0.001);
+}
+
+#[test]
+fn absorbing_medium_loses_power_with_distance() {
+    let mut medium = Medium::new(vec2(-500.0, -100.0), vec2(500.0, 100.0), GLASS, blue());
+    medium.absorption = Absorption::Constant(0.01);
+    let mut scene = Scene::new(vec![Shape::Medium(medium)]);
+    scene.fresnel = false;
+    let ray = trace(&scene, vec2(0.0, -200.0), vec2(0.0, 1.0));
+
+    // 200 through the glass keeps e^-2 of the power
+    let inside = ray.segments()[1];
+    assert_eq!(inside.power, 1.0);
+    assert!((inside.end_power - (-2.0f32).exp()).abs() < 0.001);
+    assert_eq!(ray.segments()[2].power, inside.end_power);
+    let stats = ray.stats();
+    assert!((stats.attenuated - (1.0 - (-2.0f32).exp())).abs() < 0.001);
+    assert!((stats.total() - 1.0).abs() < 0.001);
+}
+
+#[test]
+fn tinted_medium_absorbs_colors_differently() {
+    let absorption = Absorption::Colors { blue: 0.0, green: 0.01, red: 0.02 };
+    assert_eq!(absorption.at(450.0), 0.0);
+    assert!((absorption.at(600.0) - 0.015).abs() < 0.0001);
+    assert_eq!(absorption.at(800.0), 0.02);
+
+    let mut medium = Medium::new(vec2(0.0, 0.0), vec2(1.0, 1.0), GLASS, blue());
+    medium.absorption = absorption;
+    assert!(medium.transmittance(450.0, 100.0) > medium.transmittance(650.0, 100.0));
+}
+
+fn block(min: glam::Vec2, max: glam::Vec2, refractive_index: f32) -> Shape {
+    Shape::Medium(Medium::new(min, max, refractive_index, blue()))
+}
+
+/// traces without partial reflection and checks Snell's law at every vertical border: the
+/// sideways part of the direction times the index stays the same
+fn assert_snell(mut scene: Scene, start: glam::Vec2, borders: &[f32], indices: &[f32]) {
+    scene.fresnel = false;
+    let start_direction = vec2(20.0_f32.to_radians().cos(), 20.0_f32.to_radians().sin());
+    let ray = trace(&scene, start, start_direction);
+
+    let segments = ray.segments();
+    assert_eq!(segments.len(), indices.len(), "{:?}", segments);
+    for (segment, border) in segments.iter().zip(borders) {
+        assert!((segment.end.x - border).abs() < 0.001, "{:?} doesn't end at {}", segment, border);
+    }
+    for (segment, index) in segments.iter().zip(indices) {
+        assert!((direction(segment).y * index - start_direction.y * indices[0]).abs() < 0.001, "{:?} isn't in n = {}", segment, index);
+    }
+}
+
+#[test]
+fn nested_mediums_refract_into_and_out_of_each_other() {
+    // water inside glass, the ray goes back into the glass and then the air
+    let shapes = vec![
+        block(vec2(-200.0, -200.0), vec2(200.0, 200.0), GLASS),
+        block(vec2(-100.0, -150.0), vec2(100.0, 150.0), WATER),
+    ];
+    assert_snell(Scene::new(shapes), vec2(-300.0, 0.0), &[-200.0, -100.0, 100.0, 200.0], &[AIR, GLASS, WATER, GLASS, AIR]);
+}
+
+#[test]
+fn touching_mediums_refract_straight_from_one_to_the_other() {
+    let shapes = vec![
+        block(vec2(-100.0, -100.0), vec2(0.0, 100.0), WATER),
+        block(vec2(0.0, -100.0), vec2(100.0, 100.0), DIAMOND),
+    ];
+    assert_snell(Scene::new(shapes), vec2(-200.0, 0.0), &[-100.0, 0.0, 100.0], &[AIR, WATER, DIAMOND, AIR]);
+}
+
+#[test]
+fn ambient_index_is_the_first_n1() {
+    // a glass block under water
+    let mut scene = Scene::new(vec![block(vec2(-100.0, -150.0), vec2(100.0, 150.0), GLASS)]);
+    scene.ambient_index = WATER;
+    assert_snell(scene, vec2(-200.0, 0.0), &[-100.0, 100.0], &[WATER, GLASS, WATER]);
+}
@@ -0,0 +1,87 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glam::{vec2, Vec2};
+use ray_tracing::circles::Circle;
+use ray_tracing::editor::Editor;
+use ray_tracing::mediums::{Gradient, Medium, GLASS};
+use ray_tracing::rays::{Shape, Shape_Util};
+use ray_tracing::transform::Transform;
+
+mod common;
+use common::{blue, close, trace, without_fresnel};
+
+fn turned(angle: f32) -> Transform {
+    Transform {
+        translation: vec2(30.0, -20.0),
+        rotation: angle,
+        scale: 1.5,
+    }
+}
+
+fn prism() -> Medium {
+    Medium::polygon(vec![vec2(-50.0, -30.0), vec2(50.0, -30.0), vec2(0.0, 56.0)], GLASS, blue())
+}
+
+#[test]
+fn local_and_world_go_back_and_forth() {
+    let transform = turned(0.7);
+    let point = vec2(12.0, -3.0);
+    assert!(close(transform.to_local(transform.to_world(point)), point));
+    assert!(close(turned(FRAC_PI_2).to_world(vec2(10.0, 0.0)), vec2(30.0, -5.0)));
+}
+
+#[test]
+fn turned_prism_traces_like_one_with_turned_points() {
+    let transform = turned(0.4);
+    let mut moved = prism();
+    moved.transform = transform;
+    let mut baked = prism();
+    baked.points = baked.points.iter().map(|point| transform.to_world(*point)).collect();
+
+    let start = vec2(-300.0, 0.0);
+    let direction = vec2(1.0, 0.1).normalize();
+    let a = trace(&without_fresnel(vec![Shape::Medium(moved)]), start, direction);
+    let b = trace(&without_fresnel(vec![Shape::Medium(baked)]), start, direction);
+    assert_eq!(a.segments().len(), b.segments().len());
+    assert!(a.segments().iter().zip(b.segments()).all(|(a, b)| close(a.end, b.end)));
+}
+
+#[test]
+fn scaled_circle_hits_at_world_distance() {
+    let mut circle = Circle::from(Vec2::ZERO, 10.0);
+    circle.transform = turned(1.0);
+    let hit = circle.ray_intersect(vec2(-100.0, -20.0), vec2(1.0, 0.0)).unwrap();
+    assert!((hit.distance - 115.0).abs() < 0.01);
+    assert!(close(hit.normal, vec2(-1.0, 0.0)));
+    assert!(circle.intersect(&vec2(44.0, -20.0)));
+}
+
+#[test]
+fn gradient_turns_with_the_medium() {
+    let mut medium = Medium::new(vec2(-50.0, -50.0), vec2(50.0, 50.0), GLASS, blue());
+    medium.gradient = Gradient::Linear { slope: vec2(0.001, 0.0) };
+    medium.transform.rotation = FRAC_PI_2;
+    assert!((medium.index_at(589.3, vec2(0.0, 40.0)) - 1.54).abs() < 0.001);
+    assert!((medium.index_at(589.3, vec2(40.0, 0.0)) - 1.5).abs() < 0.001);
+}
+
+#[test]
+fn rotation_handle_turns_the_shape_around_its_pivot() {
+    let mut shapes = vec![Shape::Medium(prism())];
+    let mut editor = Editor::default();
+    editor.selected = Some(0);
+    let pivot = shapes[0].pivot();
+    let handle = *shapes[0].handles().last().unwrap();
+    assert!(close((handle - pivot).normalize(), vec2(1.0, 0.0)));
+
+    editor.press(&shapes, handle);
+    editor.drag(&mut shapes, pivot + vec2(0.0, 100.0));
+    editor.release();
+
+    assert!((shapes[0].transform().rotation - FRAC_PI_2).abs() < 0.001);
+    assert!(close(shapes[0].pivot(), pivot));
+    // a quarter turn counterclockwise
+    let corner = shapes[0].handles()[0];
+    let center = vec2(0.0, -4.0 / 3.0);
+    assert!(close(corner - pivot, (vec2(-50.0, -30.0) - center).perp()));
+}